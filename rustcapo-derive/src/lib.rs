@@ -0,0 +1,258 @@
+//! Provides `#[derive(CapoSettings)]` for loading a struct's fields from a
+//! RustCAPO configuration.
+//!
+//! Every field is read from the property named after it, below the struct's
+//! prefix if it has one. Attributes change how a field is read:
+//!
+//! * `#[capo(prefix = "section1.database")]` on the struct sets the prefix of
+//!   every key
+//! * `#[capo(key = "user")]` sets the key of a field, below the prefix
+//! * `#[capo(default = "5432")]` sets the value parsed when the property isn't
+//!   defined
+//! * `#[capo(optional)]` uses the type's `Default` when the property isn't
+//!   defined, which `Option` fields always do
+//! * `#[capo(required)]` makes an `Option` field's property required
+//! * `#[capo(doc = "...")]` describes a field, otherwise its doc comment is
+//!   used
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{
+    quote,
+    ToTokens,
+};
+use syn::{
+    parse_macro_input,
+    Attribute,
+    Data,
+    DeriveInput,
+    Expr,
+    ExprLit,
+    Fields,
+    GenericArgument,
+    Lit,
+    LitStr,
+    Meta,
+    PathArguments,
+    Type,
+};
+
+/// Derive `rustcapo::CapoSettings` for a struct with named fields
+#[proc_macro_derive(CapoSettings, attributes(capo))]
+pub fn derive_capo_settings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The attributes given to a field, or to the struct itself
+#[derive(Default)]
+struct CapoAttributes {
+    prefix: Option<LitStr>,
+    key: Option<LitStr>,
+    default: Option<LitStr>,
+    doc: Option<LitStr>,
+    required: bool,
+    optional: bool,
+}
+
+impl CapoAttributes {
+    /// Read the `#[capo(...)]` attributes
+    ///
+    /// # Arguments
+    /// * `attrs` - The attributes of the struct or field
+    ///
+    /// # Return
+    /// A Result with the CapoAttributes or a syn::Error for an unknown or
+    /// malformed attribute
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("capo")) {
+            attr.parse_nested_meta(|meta| {
+                let name = match meta.path.get_ident() {
+                    Some(i) => i.to_string(),
+                    None => return Err(meta.error("unknown capo attribute")),
+                };
+
+                match name.as_str() {
+                    "prefix" => parsed.prefix = Some(meta.value()?.parse()?),
+                    "key" => parsed.key = Some(meta.value()?.parse()?),
+                    "default" => parsed.default = Some(meta.value()?.parse()?),
+                    "doc" => parsed.doc = Some(meta.value()?.parse()?),
+                    "required" => parsed.required = true,
+                    "optional" => parsed.optional = true,
+                    _ => return Err(meta.error("unknown capo attribute")),
+                };
+
+                Ok(())
+            })?;
+        }
+
+        // Fall back to the doc comment for the description
+        if parsed.doc.is_none() {
+            let lines: Vec<String> = attrs.iter()
+                .filter(|a| a.path().is_ident("doc"))
+                .filter_map(|a| match &a.meta {
+                    Meta::NameValue(nv) => match &nv.value {
+                        Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) =>
+                            Some(s.value().trim().to_string()),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect();
+
+            if !lines.is_empty() {
+                parsed.doc = Some(LitStr::new(&lines.join(" "),
+                    proc_macro2::Span::call_site()));
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Get the type inside an `Option`
+///
+/// # Arguments
+/// * `ty` - The type of a field
+///
+/// # Return
+/// An Option with the inner type or None if the field isn't an `Option`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Generate the CapoSettings implementation
+///
+/// # Arguments
+/// * `input` - The parsed struct
+///
+/// # Return
+/// A Result with the generated code or a syn::Error if the struct can't
+/// derive CapoSettings
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => return Err(syn::Error::new_spanned(input,
+                "CapoSettings can only be derived for structs with named \
+                fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(input,
+            "CapoSettings can only be derived for structs")),
+    };
+
+    let struct_attrs = CapoAttributes::parse(&input.attrs)?;
+    let prefix = struct_attrs.prefix.map(|p| p.value()).unwrap_or_default();
+
+    let mut loads = Vec::new();
+    let mut assigns = Vec::new();
+    let mut infos = Vec::new();
+
+    for field in fields {
+        let attrs = CapoAttributes::parse(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named fields have names");
+
+        let name = attrs.key.map(|k| k.value())
+            .unwrap_or_else(|| ident.to_string());
+        let key = match prefix.is_empty() {
+            true => name,
+            false => format!("{}.{}", prefix.trim_end_matches('.'), name),
+        };
+
+        let inner = option_inner(&field.ty);
+        let value_type = inner.unwrap_or(&field.ty);
+        let type_name = value_type.to_token_stream().to_string()
+            .replace(' ', "");
+
+        let required = match inner {
+            Some(_) => attrs.required,
+            None => !attrs.optional && attrs.default.is_none(),
+        };
+
+        let default = match &attrs.default {
+            Some(d) => quote!(::core::option::Option::Some(#d)),
+            None => quote!(::core::option::Option::None),
+        };
+        let doc = match &attrs.doc {
+            Some(d) => quote!(::core::option::Option::Some(#d)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        loads.push(quote! {
+            let #ident = ::rustcapo::config::settings::load_setting::<
+                #value_type>(config, #key, #type_name, #default, #required,
+                &mut problems);
+        });
+
+        assigns.push(match (inner, required) {
+            (Some(_), _) => quote!(#ident: #ident),
+            (None, true) => quote!(#ident: #ident.expect("checked above")),
+            (None, false) => quote!(#ident: #ident.unwrap_or_default()),
+        });
+
+        infos.push(quote! {
+            ::rustcapo::config::settings::CapoSettingInfo {
+                key: #key,
+                type_name: #type_name,
+                default: #default,
+                required: #required,
+                description: #doc,
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::rustcapo::config::settings::CapoSettings
+            for #ident #ty_generics #where_clause {
+
+            fn from_capo(config: &::rustcapo::CapoConfig)
+                -> ::core::result::Result<Self,
+                    ::rustcapo::config::settings::CapoSettingsError> {
+
+                let mut problems = ::std::vec::Vec::new();
+                #(#loads)*
+
+                if !problems.is_empty() {
+                    return ::core::result::Result::Err(
+                        ::rustcapo::config::settings::CapoSettingsError {
+                            problems,
+                        });
+                }
+
+                ::core::result::Result::Ok(Self {
+                    #(#assigns,)*
+                })
+            }
+
+            fn describe()
+                -> ::std::vec::Vec<::rustcapo::config::settings::CapoSettingInfo> {
+
+                ::std::vec![#(#infos),*]
+            }
+        }
+    })
+}
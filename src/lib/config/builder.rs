@@ -0,0 +1,321 @@
+//! This file contains the builder for explicitly configuring a CapoConfig
+use std::{
+    collections::HashMap,
+    env,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use super::{
+    config::{
+        CapoConfig,
+        CapoFileStatus,
+    },
+    source::{
+        self,
+        ConfigSource,
+    },
+};
+use crate::helpers::{
+    errors::CapoError,
+    defaults,
+};
+
+/// The struct for configuring how a CapoConfig is loaded without relying on
+/// the process environment
+#[derive(Clone, Debug)]
+pub struct CapoConfigBuilder {
+    profile: Option<String>,
+    path: Option<Vec<PathBuf>>,
+    append_user_dir: bool,
+    profile_env_var: Option<String>,
+    path_env_var: Option<String>,
+    override_prefix: String,
+    override_vars: Option<Vec<(String, String)>>,
+    strict: bool,
+    sources: Vec<PathBuf>,
+    formats: Vec<Arc<dyn ConfigSource>>,
+    overrides: Vec<(String, String)>,
+}
+
+impl Default for CapoConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CapoConfigBuilder {
+    /// Create a CapoConfigBuilder with the same behaviour as `CapoConfig::new`
+    ///
+    /// # Return
+    /// A CapoConfigBuilder that reads the default environment variables,
+    /// appends the user's directory and skips unreadable files
+    pub fn new() -> Self {
+        Self {
+            profile: None,
+            path: None,
+            append_user_dir: true,
+            profile_env_var: Some(defaults::CAPO_ENV_VAR.to_string()),
+            path_env_var: Some(defaults::CAPO_PATH_VAR.to_string()),
+            override_prefix: defaults::CAPO_OVERRIDE_PREFIX.to_string(),
+            override_vars: None,
+            strict: false,
+            sources: Vec::new(),
+            formats: source::default_sources(),
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Set the profile to use instead of reading it from the environment
+    ///
+    /// # Arguments
+    /// * `profile` - A ToString with the name of the profile
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn profile<S: ToString>(mut self, profile: S) -> Self {
+        self.profile = Some(profile.to_string());
+        self
+    }
+
+    /// Set the directories to search instead of reading them from the
+    /// environment or using the default
+    ///
+    /// # Arguments
+    /// * `path` - A `Vec<PathBuf>` with the directories in search order
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn path(mut self, path: Vec<PathBuf>) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Set whether the user's `~/.capo` directory is appended to the path
+    ///
+    /// # Arguments
+    /// * `append` - A bool that is true if the directory should be searched
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn append_user_dir(mut self, append: bool) -> Self {
+        self.append_user_dir = append;
+        self
+    }
+
+    /// Set the name of the environment variable holding the profile
+    ///
+    /// # Arguments
+    /// * `name` - A ToString with the environment variable name
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn profile_env_var<S: ToString>(mut self, name: S) -> Self {
+        self.profile_env_var = Some(name.to_string());
+        self
+    }
+
+    /// Set the name of the environment variable holding the path
+    ///
+    /// # Arguments
+    /// * `name` - A ToString with the environment variable name
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn path_env_var<S: ToString>(mut self, name: S) -> Self {
+        self.path_env_var = Some(name.to_string());
+        self
+    }
+
+    /// Set the prefix of the environment variables that override properties.
+    /// After the prefix, `__` separates the parts of the dotted key and `_`
+    /// stands for `_` or `-`, ignoring case, so `CAPO__SECTION1__DATABASE__USER`
+    /// overrides `section1.database.user`
+    ///
+    /// # Arguments
+    /// * `prefix` - A ToString with the prefix, "CAPO__" by default, or an
+    ///   empty string to turn the overrides off
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn override_prefix<S: ToString>(mut self, prefix: S) -> Self {
+        self.override_prefix = prefix.to_string();
+        self
+    }
+
+    /// Read the variables that override properties from the given names and
+    /// values instead of the process environment. Only the names starting
+    /// with the override prefix are used
+    ///
+    /// # Arguments
+    /// * `vars` - An IntoIterator of the variable names and values
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn override_vars<I, K, V>(mut self, vars: I) -> Self
+        where I: IntoIterator<Item = (K, V)>, K: ToString, V: ToString {
+
+        self.override_vars = Some(vars.into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect());
+        self
+    }
+
+    /// Stop the profile, path and property overrides from being read from any
+    /// environment variable. Variables given with `override_vars` are still
+    /// used
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn ignore_environment(mut self) -> Self {
+        self.profile_env_var = None;
+        self.path_env_var = None;
+        self.override_vars.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Set whether files that exist but can't be read are an error instead of
+    /// being skipped with a warning. A strict configuration also fails to load
+    /// when a value can't be resolved, instead of keeping it as written
+    ///
+    /// # Arguments
+    /// * `strict` - A bool that is true if unreadable files should be errors
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Add a properties file to read after the files found on the path
+    ///
+    /// # Arguments
+    /// * `file` - An `Into<PathBuf>` with the path to the properties file
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn source(mut self, file: impl Into<PathBuf>) -> Self {
+        self.sources.push(file.into());
+        self
+    }
+
+    /// Add a format to read profile files in. Files of every format are looked
+    /// for in each directory, layered in the order the formats were added after
+    /// `.properties` and the formats enabled by cargo features
+    ///
+    /// # Arguments
+    /// * `format` - The ConfigSource that reads the format
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn format(mut self, format: impl ConfigSource + 'static) -> Self {
+        self.formats.push(Arc::new(format));
+        self
+    }
+
+    /// Give a property a value that overrides every file and environment
+    /// variable. Its location is "cli" and it can reference other properties
+    /// like any value read from a file
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key, matched ignoring case
+    /// * `value` - A ToString with the value
+    ///
+    /// # Return
+    /// The updated CapoConfigBuilder
+    pub fn with_override<K: ToString, V: ToString>(mut self, key: K, value: V)
+        -> Self {
+
+        self.overrides.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Load the configuration described by the builder
+    ///
+    /// # Return
+    /// A Result with the loaded CapoConfig or a CapoError if it couldn't be
+    /// configured
+    pub fn build(self) -> Result<CapoConfig, CapoError> {
+        let mut config = self.configure()?;
+        config.load()?;
+
+        Ok(config)
+    }
+
+    /// Find the files the configuration described by the builder reads,
+    /// without merging their properties or resolving their values, so a
+    /// strict configuration with a value that can't be resolved doesn't stop a
+    /// tool from editing its file
+    ///
+    /// # Return
+    /// A Result with the files like `CapoConfig::files` lists them, or a
+    /// CapoError if a file couldn't be read or there's no profile
+    pub fn files(self) -> Result<Vec<CapoFileStatus>, CapoError> {
+        let mut config = self.configure()?;
+        config.find_files()?;
+
+        Ok(config.files())
+    }
+
+    /// Make the CapoConfig described by the builder without reading any files
+    ///
+    /// # Return
+    /// A Result with the CapoConfig or a CapoError if it couldn't be
+    /// configured
+    fn configure(self) -> Result<CapoConfig, CapoError> {
+        // Use the provided profile, otherwise the environment variable,
+        // otherwise error
+        let profile = match (self.profile, &self.profile_env_var) {
+            (Some(p), _) => p,
+            (None, Some(var)) => match env::var(var) {
+                Ok(p) => p,
+                Err(_) => return Err(CapoError::ProfileMissing {
+                    env_var: Some(var.clone()),
+                }),
+            },
+            (None, None) => return Err(CapoError::ProfileMissing {
+                env_var: None,
+            }),
+        };
+
+        // Use the provided path, otherwise the environment variable, otherwise
+        // the default
+        let env_path = self.path_env_var.and_then(env::var_os);
+        let mut path: Vec<PathBuf> = match (self.path, env_path) {
+            (Some(p), _) => p,
+            (None, Some(p)) => env::split_paths(&p).collect(),
+            (None, None) => env::split_paths(defaults::DEFAULT_CAPO_PATH)
+                .collect(),
+        };
+
+        // Append the user's capo path
+        if self.append_user_dir {
+            match dirs::home_dir() {
+                Some(h) => path.push(h.join(".capo")),
+                None => return Err(CapoError::HomeDirMissing),
+            };
+        }
+
+        let config = CapoConfig {
+            profile,
+            path,
+            options: HashMap::new(),
+            raw_options: HashMap::new(),
+            locations: HashMap::new(),
+            history: HashMap::new(),
+            unresolved: HashMap::new(),
+            keys: HashMap::new(),
+            cfg_files: Vec::new(),
+            sources: self.sources,
+            formats: self.formats,
+            override_prefix: self.override_prefix,
+            override_vars: self.override_vars,
+            overrides: self.overrides,
+            strict: self.strict,
+            skipped: HashMap::new(),
+        };
+
+        Ok(config)
+    }
+}
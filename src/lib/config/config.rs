@@ -1,24 +1,130 @@
 //! This file contains the struct and methods to handle the CAPO configuration
-use dirs;
 use std::{
-    collections::HashMap,
+    any,
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
     env,
-    path::PathBuf, error::Error,
+    fmt,
+    fs,
+    io::ErrorKind,
+    mem,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+    sync::Arc,
+    time::{
+        Duration,
+        SystemTime,
+    },
 };
 
-use super::config_file::CapoConfigurationFile;
+use url::Url;
+
+use super::{
+    builder::CapoConfigBuilder,
+    config_file::{
+        modified_time,
+        CapoConfigurationFile,
+    },
+    interpolate,
+    list::ListOptions,
+    source::{
+        ConfigSource,
+        PropertiesSource,
+    },
+};
 use crate::helpers::{
-    errors,
-    defaults,
+    errors::{
+        CapoError,
+        CapoValueError,
+    },
+    keys::strip_prefix_ignoring_case,
+    values,
 };
 
-/// The struct for fetching CAPO configuration settings
+/// A value that one of the loaded files gave a CAPO property. Values from an
+/// environment variable or override have an empty file and a location like
+/// `env:NAME` or `cli`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapoHistoryEntry {
+    pub value: String,
+    pub location: String,
+    pub file: PathBuf,
+    pub profile: Option<String>,
+}
+
+impl CapoHistoryEntry {
+    /// Describe where the value was given, telling apart files with the same
+    /// name in different directories of the path
+    ///
+    /// # Return
+    /// A String with the full path of the file, or the location of a value
+    /// from an environment variable or override
+    pub fn source(&self) -> String {
+        match self.file.as_os_str().is_empty() {
+            true => self.location.clone(),
+            _ => self.file.display().to_string(),
+        }
+    }
+}
+
+/// A file that was looked for while loading a configuration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapoFileStatus {
+    pub file: PathBuf,
+    pub profile: Option<String>,
+    pub skipped: Option<String>,
+}
+
+/// A file that wasn't read, with when it was last modified so a refresh can
+/// tell if it changed
+#[derive(Clone, Debug)]
+pub(crate) struct SkippedFile {
+    modified: Option<SystemTime>,
+    profile: Option<String>,
+    reason: String,
+}
+
+/// The struct for fetching CAPO configuration settings. Properties are kept
+/// under their keys as written in the files, while lookups ignore case
 pub struct CapoConfig {
     pub profile: String,
-    pub path: String,
+    pub path: Vec<PathBuf>,
     pub options: HashMap<String, String>,
+    pub raw_options: HashMap<String, String>,
     pub locations: HashMap<String, String>,
+    pub history: HashMap<String, Vec<CapoHistoryEntry>>,
+    pub(crate) unresolved: HashMap<String, CapoError>,
+    pub(crate) keys: HashMap<String, String>,
     pub cfg_files: Vec<CapoConfigurationFile>,
+    pub(crate) sources: Vec<PathBuf>,
+    pub(crate) formats: Vec<Arc<dyn ConfigSource>>,
+    pub(crate) override_prefix: String,
+    pub(crate) override_vars: Option<Vec<(String, String)>>,
+    pub(crate) overrides: Vec<(String, String)>,
+    pub(crate) strict: bool,
+    pub(crate) skipped: HashMap<PathBuf, SkippedFile>,
+}
+
+/// A file being read while the files it extends or includes are read first
+struct Layer {
+    canonical: PathBuf,
+    file: PathBuf,
+    name: String,
+}
+
+/// The files read so far while loading a configuration
+#[derive(Default)]
+struct ReadPass {
+    reuse: bool,
+    changed: bool,
+    files: Vec<CapoConfigurationFile>,
+    skipped: HashMap<PathBuf, SkippedFile>,
+    stack: Vec<Layer>,
 }
 
 impl CapoConfig {
@@ -26,94 +132,691 @@ impl CapoConfig {
     ///
     /// # Arguments
     /// * `profile` - An `Option<ToString>` with a provided profile or None if the
-    /// environment variable is to be used
-    /// * `path` - An `Option<ToString>` With a provided path or None if the
-    /// environment variable is to be used
+    ///   environment variable is to be used
+    /// * `path` - An `Option<ToString>` With a provided colon delimited path or
+    ///   None if the environment variable is to be used
     ///
     /// # Return
-    /// A CapoProfile object constructed from the path and profile
+    /// A Result with a CapoConfig object constructed from the path and profile
+    /// or a CapoError if it couldn't be configured
     pub fn new<S: ToString>(profile: Option<S>, path: Option<S>)
-        -> Result<Self, Box<dyn Error>> {
-
-        // Use the provided profile, otherwise the environment variable,
-        // otherwise error
-        let profile = match profile {
-            Some(p) => p.to_string(),
-            None => match env::var(defaults::CAPO_ENV_VAR) {
-                Ok(p) => p,
-                Err(_) => {
-                    errors::PROFILE_ERROR.exit_on_error();
-                    String::new()
+        -> Result<Self, CapoError> {
+
+        let mut builder = CapoConfigBuilder::new();
+
+        if let Some(p) = profile {
+            builder = builder.profile(p);
+        }
+
+        if let Some(p) = path {
+            builder = builder.path(env::split_paths(&p.to_string()).collect());
+        }
+
+        builder.build()
+    }
+
+    /// Get a CapoConfigBuilder to explicitly configure a new CapoConfig
+    ///
+    /// # Return
+    /// A CapoConfigBuilder with the same defaults as `CapoConfig::new`
+    pub fn builder() -> CapoConfigBuilder {
+        CapoConfigBuilder::new()
+    }
+
+    /// Read the configuration files, merge their properties and resolve the
+    /// references in their values
+    ///
+    /// # Return
+    /// A Result with nothing or a CapoError if no files could be read, or the
+    /// config is strict and a value couldn't be resolved
+    pub(crate) fn load(&mut self) -> Result<(), CapoError> {
+        self.read_files(false).map(|_| ())
+    }
+
+    /// Re-read every configuration file on the path and rebuild the merged
+    /// properties. The current properties are kept if the new ones can't be
+    /// loaded
+    ///
+    /// # Return
+    /// A Result with nothing or a CapoError if no files could be read, or the
+    /// config is strict and a value couldn't be resolved
+    pub fn reload(&mut self) -> Result<(), CapoError> {
+        self.load()
+    }
+
+    /// Re-read only the configuration files whose modification time changed
+    /// since they were last read, including files that didn't exist before,
+    /// and rebuild the merged properties if any did. The current properties
+    /// are kept if the new ones can't be loaded
+    ///
+    /// # Return
+    /// A Result with true if any file changed and false otherwise, or a
+    /// CapoError if no files could be read, or the config is strict and a value
+    /// couldn't be resolved
+    pub fn refresh_if_changed(&mut self) -> Result<bool, CapoError> {
+        self.read_files(true)
+    }
+
+    /// Give a property a value that overrides every file and environment
+    /// variable, like `CapoConfigBuilder::with_override`. The override is kept
+    /// when the configuration is reloaded
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key, matched ignoring case
+    /// * `value` - A ToString with the value
+    ///
+    /// # Return
+    /// A Result with the updated CapoConfig or a CapoError if the config is
+    /// strict and a value couldn't be resolved with the override in place
+    pub fn with_override<K: ToString, V: ToString>(mut self, key: K, value: V)
+        -> Result<Self, CapoError> {
+
+        self.overrides.push((key.to_string(), value.to_string()));
+
+        // The files don't change, only the properties merged from them
+        let files = mem::take(&mut self.cfg_files);
+        let skipped = mem::take(&mut self.skipped);
+        self.load_files(files, skipped)?;
+
+        Ok(self)
+    }
+
+    /// Get every file that was looked for while loading the configuration
+    ///
+    /// # Return
+    /// A `Vec<CapoFileStatus>` of the files that were read, in the order they
+    /// were read, followed by the files that were skipped with the reason
+    /// they were skipped, sorted by path
+    pub fn files(&self) -> Vec<CapoFileStatus> {
+        let read = self.cfg_files.iter().map(|f| CapoFileStatus {
+            file: f.path_to_file.clone(),
+            profile: f.profile.clone(),
+            skipped: None,
+        });
+
+        let mut skipped: Vec<CapoFileStatus> = self.skipped.iter()
+            .map(|(file, s)| CapoFileStatus {
+                file: file.clone(),
+                profile: s.profile.clone(),
+                skipped: Some(s.reason.clone()),
+            })
+            .collect();
+        skipped.sort_by(|a, b| a.file.cmp(&b.file));
+
+        read.chain(skipped).collect()
+    }
+
+    /// Get the profiles that have a file in any directory on the path
+    ///
+    /// # Return
+    /// A `Vec<String>` of the profile names, sorted and without duplicates
+    pub fn available_profiles(&self) -> Vec<String> {
+        let extensions: Vec<&str> = self.formats.iter()
+            .flat_map(|f| f.extensions().iter().copied())
+            .collect();
+
+        let mut profiles: Vec<String> = self.path.iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|e| e.ok()))
+            .map(|entry| entry.path())
+            .filter(|file| file.is_file() && file.extension()
+                .is_some_and(|e| extensions.contains(&&*e.to_string_lossy())))
+            .filter_map(|file| file.file_stem()
+                .map(|s| s.to_string_lossy().to_string()))
+            .collect();
+        profiles.sort();
+        profiles.dedup();
+
+        profiles
+    }
+
+    /// Get the profiles the configuration layers, in the order they're read
+    ///
+    /// # Return
+    /// A `Vec<String>` of the comma separated names in the profile
+    pub fn profiles(&self) -> Vec<String> {
+        self.profile.split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect()
+    }
+
+    /// Get every file that may hold properties for the profiles, in the order
+    /// they're read. Profiles are layered in every directory before moving to
+    /// the next one, and the profiles they extend aren't included. Files in
+    /// formats other than `.properties` are only included if they exist
+    ///
+    /// # Return
+    /// A `Vec<(PathBuf, Option<String>)>` with each file and the profile it's
+    /// for, or None if it's an explicit source rather than a profile file on
+    /// the path, which may be missing
+    pub(crate) fn candidate_files(&self) -> Vec<(PathBuf, Option<String>)> {
+        let mut files = Vec::new();
+
+        for dir in &self.path {
+            for profile in self.profiles() {
+                let extensions = self.formats.iter()
+                    .flat_map(|f| f.extensions().iter())
+                    .enumerate();
+
+                // Only the .properties file is looked for even if it's missing
+                for (i, extension) in extensions {
+                    let file = dir.join(format!("{}.{}", profile, extension));
+                    if i == 0 || file.is_file() {
+                        files.push((file, Some(profile.clone())));
+                    }
+                }
+            }
+        }
+
+        let source_files = self.sources.iter().map(|f| (f.clone(), None));
+        files.extend(source_files);
+
+        files
+    }
+
+    /// Find the files of a profile a file extends in the same directory,
+    /// layered like the files of a profile on the path
+    ///
+    /// # Arguments
+    /// * `dir` - A &Path with the directory of the extending file
+    /// * `profile` - A &str with the name of the extended profile
+    ///
+    /// # Return
+    /// A `Vec<PathBuf>` with the file of the profile in every format that
+    /// exists, in the order the formats are read, or its `.properties` file
+    /// if none do
+    fn profile_files(&self, dir: &Path, profile: &str) -> Vec<PathBuf> {
+        let files: Vec<PathBuf> = self.formats.iter()
+            .flat_map(|f| f.extensions().iter())
+            .map(|ext| dir.join(format!("{}.{}", profile, ext)))
+            .filter(|file| file.is_file())
+            .collect();
+
+        match files.is_empty() {
+            true => vec![dir.join(format!("{}.properties", profile))],
+            _ => files,
+        }
+    }
+
+    /// Get the format of a file from its extension
+    ///
+    /// # Arguments
+    /// * `file` - A &Path with the location of the file
+    ///
+    /// # Return
+    /// The ConfigSource for the file's extension, or for `.properties` if no
+    /// format has the extension
+    fn format_of(&self, file: &Path) -> Arc<dyn ConfigSource> {
+        let extension = file.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        self.formats.iter()
+            .find(|f| f.extensions().contains(&extension.as_str()))
+            .or(self.formats.first())
+            .cloned()
+            .unwrap_or_else(|| Arc::new(PropertiesSource))
+    }
+
+    /// Get every file the configuration depends on, whether it was read or
+    /// skipped
+    ///
+    /// # Return
+    /// A `Vec<PathBuf>` of the files without duplicates
+    #[cfg_attr(not(feature = "watch"), allow(dead_code))]
+    pub(crate) fn tracked_files(&self) -> Vec<PathBuf> {
+        let mut tracked: Vec<PathBuf> = Vec::new();
+        let files = self.candidate_files().into_iter().map(|(f, _)| f)
+            .chain(self.cfg_files.iter().map(|f| f.path_to_file.clone()))
+            .chain(self.skipped.keys().cloned());
+
+        for file in files {
+            if !tracked.contains(&file) {
+                tracked.push(file);
+            }
+        }
+
+        tracked
+    }
+
+    /// Read every candidate file along with the profiles they extend, and
+    /// rebuild the merged properties from them
+    ///
+    /// # Arguments
+    /// * `reuse` - A bool that is true to keep files whose modification time
+    ///   hasn't changed instead of reading them again, and to only rebuild the
+    ///   properties if a file changed
+    ///
+    /// # Return
+    /// A Result with true if the properties were rebuilt and false otherwise,
+    /// or a CapoError if no files could be read, or the config is strict and a
+    /// value couldn't be resolved
+    fn read_files(&mut self, reuse: bool) -> Result<bool, CapoError> {
+        let pass = self.read_pass(reuse)?;
+        if pass.changed {
+            self.load_files(pass.files, pass.skipped)?;
+        }
+
+        Ok(pass.changed)
+    }
+
+    /// Read every candidate file along with the profiles they extend, without
+    /// merging their properties or resolving their values
+    ///
+    /// # Return
+    /// A Result with nothing or a CapoError if a file couldn't be read or
+    /// files extend or include each other in a cycle
+    pub(crate) fn find_files(&mut self) -> Result<(), CapoError> {
+        let pass = self.read_pass(false)?;
+        self.cfg_files = pass.files;
+        self.skipped = pass.skipped;
+
+        Ok(())
+    }
+
+    /// Read every candidate file along with the profiles they extend
+    ///
+    /// # Arguments
+    /// * `reuse` - A bool that is true to keep files whose modification time
+    ///   hasn't changed instead of reading them again
+    ///
+    /// # Return
+    /// A Result with the ReadPass holding the files that were read and
+    /// skipped, or a CapoError if a file couldn't be read or files extend or
+    /// include each other in a cycle
+    fn read_pass(&self, reuse: bool) -> Result<ReadPass, CapoError> {
+        let mut pass = ReadPass {
+            reuse,
+            changed: !reuse,
+            ..ReadPass::default()
+        };
+
+        for (file, profile) in self.candidate_files() {
+            // A layer that was already read as the parent of an earlier layer
+            // moves over it, since later layers override earlier ones
+            pass.files.retain(|f| f.path_to_file != file);
+
+            let optional = profile.is_some();
+            self.read_layer(&file, profile, optional, false, &mut pass)?;
+        }
+
+        Ok(pass)
+    }
+
+    /// Read a single configuration file after the profiles it extends, which
+    /// are found in the same directory, and the files it includes. Missing or
+    /// unreadable files are skipped over unless the config is strict, where
+    /// only profile files missing from the path are skipped
+    ///
+    /// # Arguments
+    /// * `file` - A &Path with the location of the file
+    /// * `profile` - An `Option<String>` with the profile the file is for
+    /// * `optional` - A bool that is true if the file may be missing
+    /// * `included` - A bool that is true if another file includes this one
+    /// * `pass` - The ReadPass to add the file and the files it uses to
+    ///
+    /// # Return
+    /// A Result with nothing or the CapoError for a file that couldn't be read
+    /// or files that extend or include each other in a cycle
+    fn read_layer(&self, file: &Path, profile: Option<String>, optional: bool,
+        included: bool, pass: &mut ReadPass) -> Result<(), CapoError> {
+
+        // A file that uses itself, directly or not, is never finished. Paths
+        // are compared canonically since includes can reach a file many ways
+        let canonical = fs::canonicalize(file)
+            .unwrap_or_else(|_| file.to_path_buf());
+        if let Some(start) = pass.stack.iter()
+            .position(|layer| layer.canonical == canonical) {
+
+            let cycle = &pass.stack[start..];
+            return Err(match included {
+                true => CapoError::IncludeCycle {
+                    chain: cycle.iter()
+                        .map(|layer| layer.file.clone())
+                        .chain([file.to_path_buf()])
+                        .collect(),
                 },
+                _ => CapoError::ProfileCycle {
+                    chain: cycle.iter()
+                        .map(|layer| layer.name.clone())
+                        .chain([cycle[0].name.clone()])
+                        .collect(),
+                },
+            });
+        }
+
+        // Files shared by several layers are only read once, where the first
+        // of them uses it
+        if pass.files.iter().any(|f| f.path_to_file == file) {
+            return Ok(());
+        }
+
+        let current = modified_time(file);
+        let cached = self.cfg_files.iter()
+            .find(|f| pass.reuse && f.path_to_file == file
+                && f.last_modified == current);
+        let unchanged_skip = self.skipped.get(file)
+            .filter(|s| pass.reuse && s.modified == current);
+        if let Some(skipped) = unchanged_skip {
+            pass.skipped.insert(file.to_path_buf(), skipped.clone());
+            return Ok(());
+        }
+
+        let mut cfg_file = match cached {
+            Some(f) => f.clone(),
+            None => {
+                pass.changed = true;
+                let format = self.format_of(file);
+                match CapoConfigurationFile::from_source(file, &*format) {
+                    Ok(f) => f,
+                    Err(e) => {
+                        pass.skipped.insert(file.to_path_buf(), SkippedFile {
+                            modified: current,
+                            profile,
+                            reason: self.skip_reason(e, optional)?,
+                        });
+                        return Ok(());
+                    },
+                }
             },
         };
+        cfg_file.profile = profile;
+
+        pass.stack.push(Layer {
+            canonical,
+            file: file.to_path_buf(),
+            name: cfg_file.profile.clone()
+                .unwrap_or_else(|| cfg_file.filename.clone()),
+        });
+
+        let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+        for parent in &cfg_file.extends {
+            for parent_file in self.profile_files(&dir, parent) {
+                self.read_layer(&parent_file, Some(parent.clone()), false,
+                    false, pass)?;
+            }
+        }
+
+        // Included files belong to the includer's profile and are overridden
+        // by it
+        for include in cfg_file.include_files()? {
+            self.read_layer(&include, cfg_file.profile.clone(), false, true,
+                pass)?;
+        }
+        pass.stack.pop();
+
+        pass.files.push(cfg_file);
+        Ok(())
+    }
+
+    /// Decide whether a file that couldn't be read is skipped, with a
+    /// warning unless the config is strict, or is an error
+    ///
+    /// # Arguments
+    /// * `e` - The CapoError the file couldn't be read with
+    /// * `optional` - A bool that is true if the file may be missing
+    ///
+    /// # Return
+    /// A Result with a String saying why the file was skipped, or the
+    /// CapoError if it can't be skipped
+    fn skip_reason(&self, e: CapoError, optional: bool)
+        -> Result<String, CapoError> {
+
+        let missing = matches!(&e, CapoError::Io { source, .. }
+            if source.kind() == ErrorKind::NotFound);
+
+        match self.strict {
+            true if !(optional && missing) => return Err(e),
+            true => {},
+            false => eprintln!("WARNING: {}", e),
+        };
+
+        match e {
+            CapoError::Io { source, .. } => Ok(source.to_string()),
+            e => Ok(e.to_string()),
+        }
+    }
+
+    /// Replace the loaded configuration files and rebuild the merged
+    /// properties from them, keeping the previous files and properties if the
+    /// new ones can't be used
+    ///
+    /// # Arguments
+    /// * `files` - The configuration files that were read, in order
+    /// * `skipped` - A HashMap of the files that weren't read and why
+    ///
+    /// # Return
+    /// A Result with nothing or a CapoError if there are no files, or the
+    /// config is strict and a value couldn't be resolved or two keys differ
+    /// only in case
+    fn load_files(&mut self, files: Vec<CapoConfigurationFile>,
+        skipped: HashMap<PathBuf, SkippedFile>)
+        -> Result<(), CapoError> {
+
+        if files.is_empty() {
+            return Err(CapoError::NoFilesFound {
+                profile: self.profile.clone(),
+                path: self.path.clone(),
+            });
+        }
+
+        let previous = mem::replace(&mut self.cfg_files, files);
+        let conflicts = self.load_merged_config();
 
-        // Use the provided path, otherwise the environment variable, otherwise
-        // the default
-        let mut path = match path {
-            Some(p) => p.to_string(),
-            None => match env::var(defaults::CAPO_PATH_VAR) {
-                Ok(p) => p,
-                Err(_) => defaults::DEFAULT_CAPO_PATH.to_string(),
+        // Keys spelled two ways are only an error in strict mode
+        if !self.strict {
+            for e in &conflicts {
+                eprintln!("WARNING: {}", e);
             }
+        }
+
+        let (options, mut unresolved) =
+            interpolate::resolve_all(&self.raw_options);
+
+        // A value that can't be resolved is kept as written and reported when
+        // it's read, unless the config is strict
+        let error = match self.strict {
+            true => conflicts.into_iter().next().or_else(|| {
+                let first = unresolved.keys().min()?.clone();
+                unresolved.remove(&first)
+            }),
+            false => None,
         };
 
-        // Append the user's capo path
-        let home_dir = match dirs::home_dir() {
-            Some(h) => h,
+        match error {
+            Some(e) => {
+                self.cfg_files = previous;
+                self.load_merged_config();
+                return Err(e);
+            },
             None => {
-                errors::HOME_DIR_ERROR.exit_on_error();
-                PathBuf::new()
+                self.options = options;
+                self.unresolved = unresolved;
             },
         };
 
-        path.push_str(format!(":{}.capo", home_dir.display()).as_str());
+        self.skipped = skipped;
+        Ok(())
+    }
 
-        let mut config = Self {
-            profile,
-            path,
-            options: HashMap::new(),
-            locations: HashMap::new(),
-            cfg_files: Vec::new(),
-        };
+    /// Create a single list of unresolved properties from all loaded
+    /// configuration files, keeping every value each property was given in the
+    /// order it was read. Keys that differ only in case are the same property,
+    /// spelled the way the last file to set it spelled it
+    ///
+    /// # Return
+    /// A `Vec<CapoError>` with a conflict for each key that was spelled
+    /// differently from the one before it
+    fn load_merged_config(&mut self) -> Vec<CapoError> {
+        self.raw_options.clear();
+        self.locations.clear();
+        self.history.clear();
+        self.keys.clear();
+
+        let mut conflicts = Vec::new();
+        for file in &self.cfg_files {
+            // Sorted so a conflict inside one file is always resolved the
+            // same way
+            let mut keys: Vec<&String> = file.options.keys().collect();
+            keys.sort();
+
+            for key in keys {
+                let upper = key.to_uppercase();
+                let previous = self.keys.insert(upper, key.clone());
+                let mut history = match previous {
+                    Some(p) if p != *key => {
+                        self.raw_options.remove(&p);
+                        self.locations.remove(&p);
+                        conflicts.push(CapoError::CaseConflict {
+                            first: p.clone(),
+                            second: key.clone(),
+                            file: file.path_to_file.clone(),
+                        });
+
+                        self.history.remove(&p).unwrap_or_default()
+                    },
+                    _ => self.history.remove(key).unwrap_or_default(),
+                };
 
-        // Get config files and properties
-        config.get_files();
-        if config.cfg_files.len() <= 0 {
-            errors::PATH_ERROR.exit_on_error();
+                history.push(CapoHistoryEntry {
+                    value: file.options[key].clone(),
+                    location: file.filename.clone(),
+                    file: file.path_to_file.clone(),
+                    profile: file.profile.clone(),
+                });
+
+                self.raw_options.insert(key.clone(), file.options[key].clone());
+                self.locations.insert(key.clone(), file.filename.clone());
+                self.history.insert(key.clone(), history);
+            }
         }
 
-        config.load_merged_config();
+        self.load_env_overrides();
+
+        // Overrides given by the program beat the environment
+        for (key, value) in self.overrides.clone() {
+            let key = self.keys.get(&key.to_uppercase())
+                .cloned()
+                .unwrap_or(key);
+            self.apply_override(&key, &value, "cli");
+        }
 
-        Ok(config)
+        conflicts
     }
 
-    /// Create a single properties list from all loaded configuration files
-    fn load_merged_config(&mut self) {
-        for file in &self.cfg_files {
-            for (key, val) in &file.options {
-                self.options.insert(key.clone().to_uppercase(), val.clone());
-                self.locations.insert(key.clone().to_uppercase(),
-                    file.filename.clone());
+    /// Override properties with the environment variables that start with the
+    /// override prefix. Each variable overrides every key it matches, or adds
+    /// a lowercase key if it matches none
+    fn load_env_overrides(&mut self) {
+        let prefix = self.override_prefix.clone();
+        if prefix.is_empty() {
+            return;
+        }
+
+        // Sorted so overrides are always applied in the same order
+        let vars = match &self.override_vars {
+            Some(vars) => vars.clone(),
+            None => env::vars().collect(),
+        };
+        let mut vars: Vec<(String, String)> = vars.into_iter()
+            .filter(|(name, _)| name.starts_with(&prefix)
+                && name.len() > prefix.len())
+            .collect();
+        vars.sort();
+
+        for (name, value) in vars {
+            let wanted = name[prefix.len()..].to_uppercase();
+            let mut keys: Vec<String> = self.keys.values()
+                .filter(|k| env_name(k) == wanted)
+                .cloned()
+                .collect();
+
+            if keys.is_empty() {
+                keys.push(wanted.replace("__", ".").to_lowercase());
+            }
+
+            for key in keys {
+                self.apply_override(&key, &value, &format!("env:{}", name));
             }
         }
     }
 
-    /// Populate the CapoConfig object's configuration file list
-    fn get_files(&mut self) {
-        for path in self.path.split(':') {
-            match CapoConfigurationFile::new(self.profile.clone(),
-                path.clone()) {
+    /// Give a CAPO property a value that takes priority over every file
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the property key as it's written in the files,
+    ///   or a new key
+    /// * `value` - A &str with the value
+    /// * `location` - A &str describing where the value came from
+    fn apply_override(&mut self, key: &str, value: &str, location: &str) {
+        self.keys.insert(key.to_uppercase(), key.to_string());
+        self.raw_options.insert(key.to_string(), value.to_string());
+        self.locations.insert(key.to_string(), location.to_string());
+        self.history.entry(key.to_string())
+            .or_default()
+            .push(CapoHistoryEntry {
+                value: value.to_string(),
+                location: location.to_string(),
+                file: PathBuf::new(),
+                profile: None,
+            });
+    }
 
-                Some(c) => self.cfg_files.push(c),
-                None => continue,
-            };
+    /// Find the resolved value of a CAPO property
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// An Option with a reference to the value or None if the property isn't
+    /// found
+    fn lookup<S: ToString>(&self, key: &S) -> Option<&String> {
+        self.options.get(self.keys.get(&key.to_string().to_uppercase())?)
+    }
+
+    /// Parse the resolved value of a CAPO property, describing the property
+    /// and where it came from if it can't be parsed
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    /// * `type_name` - A &str naming the type the value is parsed as
+    /// * `parse` - The function parsing the value, returning why it failed
+    ///
+    /// # Return
+    /// A Result with an Option of the parsed value, None if the property isn't
+    /// found, or a CapoValueError if the value can't be parsed
+    pub(crate) fn parse_with<T, S, F>(&self, key: &S, type_name: &str, parse: F)
+        -> Result<Option<T>, CapoValueError>
+        where S: ToString, F: FnOnce(&str) -> Result<T, String> {
+
+        let val = match self.lookup(key) {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let parsed = match self.get_unresolved(key) {
+            Some(e) => Err(e.to_string()),
+            None => parse(val),
+        };
+
+        match parsed {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(reason) => Err(CapoValueError::Invalid {
+                key: key.to_string(),
+                value: val.clone(),
+                location: self.get_source(key),
+                type_name: type_name.to_string(),
+                reason,
+            }),
         }
     }
 
     // Methods for retrieving properties
 
-    /// Get the value of a CAPO property as a String
+    /// Get the value of a CAPO property as a String, with the references it
+    /// contains resolved. A value whose references can't be resolved is
+    /// returned as written, while the typed getters report why
     ///
     /// # Arguments
     /// * `key` - A ToString with the property key value
@@ -122,10 +825,250 @@ impl CapoConfig {
     /// An Option with the property value as a String or None if the property
     /// isn't found
     pub fn get<S: ToString>(&self, key: &S) -> Option<String> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(v) => Some(v.clone()),
-            None => None,
+        self.lookup(key).cloned()
+    }
+
+    /// Get why the references in a CAPO property's value couldn't be
+    /// resolved, leaving the value as it was written
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// An Option with the CapoError for the undefined reference or cycle, or
+    /// None if the property isn't found or its value was resolved
+    pub fn get_unresolved<S: ToString>(&self, key: &S) -> Option<&CapoError> {
+        self.unresolved.get(self.keys.get(&key.to_string().to_uppercase())?)
+    }
+
+    /// Get the value of a CAPO property parsed as any type implementing
+    /// `FromStr`. A bool is also read from "yes" or "no", in any case
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// A Result with an Option of the parsed value, None if the property isn't
+    /// found, or a CapoValueError if the value can't be parsed as the type
+    pub fn get_as<T, S>(&self, key: &S) -> Result<Option<T>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.parse_with(key, &short_type_name::<T>(), parse_value::<T>)
+    }
+
+    /// Get the value of a CAPO property parsed as any type implementing
+    /// `FromStr`, or a default if the property isn't found
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    /// * `default` - The value to use if the property isn't found
+    ///
+    /// # Return
+    /// A Result with the parsed value or the default, or a CapoValueError if
+    /// the value can't be parsed as the type
+    pub fn get_or<T, S>(&self, key: &S, default: T) -> Result<T, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        Ok(self.get_as(key)?.unwrap_or(default))
+    }
+
+    /// Get the value of a CAPO property that must be defined, parsed as any
+    /// type implementing `FromStr`
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// A Result with the parsed value or a CapoValueError if the property isn't
+    /// found or can't be parsed as the type
+    pub fn require<T, S>(&self, key: &S) -> Result<T, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.get_as(key)?.ok_or_else(|| CapoValueError::Missing {
+            key: key.to_string(),
+        })
+    }
+
+    /// Get the value of a CAPO property as a String exactly as it was read,
+    /// without resolving the references it contains
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// An Option with the unresolved property value as a String or None if the
+    /// property isn't found
+    pub fn get_raw<S: ToString>(&self, key: &S) -> Option<String> {
+        self.raw_options.get(self.keys.get(&key.to_string().to_uppercase())?)
+            .cloned()
+    }
+
+    /// Get the value of a CAPO property as a list of comma separated elements,
+    /// each parsed as any type implementing `FromStr`
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// A Result with an Option of the elements, None if the property isn't
+    /// found, or a CapoValueError for the first element that can't be parsed
+    pub fn get_list<T, S>(&self, key: &S)
+        -> Result<Option<Vec<T>>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.get_list_with(key, &ListOptions::default())
+    }
+
+    /// Get the value of a CAPO property as a list split with the given
+    /// options, each element parsed as any type implementing `FromStr`
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    /// * `options` - The ListOptions to split the value with
+    ///
+    /// # Return
+    /// A Result with an Option of the elements, None if the property isn't
+    /// found, or a CapoValueError for the value if it can't be split or for
+    /// the first element that can't be parsed
+    pub fn get_list_with<T, S>(&self, key: &S, options: &ListOptions)
+        -> Result<Option<Vec<T>>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        let elements = match self.parse_with(key, "list",
+            |val| options.split(val))? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+
+        let location = self.get_source(key);
+        elements.into_iter()
+            .enumerate()
+            .map(|(i, element)| parse_value::<T>(&element)
+                .map_err(|reason| CapoValueError::Invalid {
+                    key: format!("{}[{}]", key.to_string(), i),
+                    value: element.clone(),
+                    location: location.clone(),
+                    type_name: short_type_name::<T>(),
+                    reason,
+                }))
+            .collect::<Result<Vec<T>, CapoValueError>>()
+            .map(Some)
+    }
+
+    /// Get the CAPO properties numbered below a prefix, like `hosts.0` and
+    /// `hosts.1` or `hosts[0]` and `hosts[1]`, as a list in numeric order.
+    /// If both forms give the same number, `prefix.N` is used
+    ///
+    /// # Arguments
+    /// * `prefix` - A ToString with the key the numbers follow
+    ///
+    /// # Return
+    /// A Result with an Option of the elements, None if no numbered
+    /// properties are found, or a CapoValueError for the first element that
+    /// can't be parsed
+    pub fn get_indexed_list<T, S>(&self, prefix: &S)
+        -> Result<Option<Vec<T>>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        let prefix = prefix.to_string();
+        let mut indexed: BTreeMap<u64, (bool, String)> = BTreeMap::new();
+
+        for key in self.keys.values() {
+            let rest = match strip_prefix_ignoring_case(key, &prefix) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            let (index, dotted) = match (rest.strip_prefix('.'),
+                rest.strip_prefix('[').and_then(|r| r.strip_suffix(']'))) {
+                (Some(i), _) => (i, true),
+                (_, Some(i)) => (i, false),
+                _ => continue,
+            };
+
+            let index = match index.parse::<u64>() {
+                Ok(i) if index.bytes().all(|b| b.is_ascii_digit()) => i,
+                _ => continue,
+            };
+
+            // Name the element the way the prefix was given
+            let name = format!("{}{}", prefix, rest);
+            match indexed.get(&index) {
+                Some((true, _)) if !dotted => continue,
+                _ => indexed.insert(index, (dotted, name)),
+            };
+        }
+
+        if indexed.is_empty() {
+            return Ok(None);
         }
+
+        indexed.values()
+            .map(|(_, name)| self.parse_with(name, &short_type_name::<T>(),
+                    parse_value::<T>)
+                .map(|v| v.expect("the key was found above")))
+            .collect::<Result<Vec<T>, CapoValueError>>()
+            .map(Some)
+    }
+
+    /// Get the value of a CAPO property as a Duration, written as whole
+    /// numbers followed by units like "30s", "5m" or "1h30m"
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// A Result with an Option of the Duration, None if the property isn't
+    /// found, or a CapoValueError if the value isn't a valid duration
+    pub fn get_duration<S: ToString>(&self, key: &S)
+        -> Result<Option<Duration>, CapoValueError> {
+
+        self.parse_with(key, "duration", values::parse_duration)
+    }
+
+    /// Get the value of a CAPO property as a number of bytes, written like
+    /// "512MiB" or "2G"
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// A Result with an Option of the number of bytes as a u64, None if the
+    /// property isn't found, or a CapoValueError if the value isn't a valid
+    /// size
+    pub fn get_bytesize<S: ToString>(&self, key: &S)
+        -> Result<Option<u64>, CapoValueError> {
+
+        self.parse_with(key, "byte size", values::parse_bytesize)
+    }
+
+    /// Get the value of a CAPO property as a PathBuf, with a leading `~`
+    /// replaced by the user's home directory
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// A Result with an Option of the PathBuf, None if the property isn't
+    /// found, or a CapoValueError if the value isn't a valid path
+    pub fn get_path<S: ToString>(&self, key: &S)
+        -> Result<Option<PathBuf>, CapoValueError> {
+
+        self.parse_with(key, "path", values::expand_path)
+    }
+
+    /// Get the value of a CAPO property as a validated Url
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// A Result with an Option of the Url, None if the property isn't found,
+    /// or a CapoValueError if the value isn't a valid URL
+    pub fn get_url<S: ToString>(&self, key: &S)
+        -> Result<Option<Url>, CapoValueError> {
+
+        self.parse_with(key, "URL", values::parse_url)
     }
 
     /// Get the value of a CAPO property as a u128
@@ -137,10 +1080,7 @@ impl CapoConfig {
     /// An Option with the u128 value if the property can be represented as a u128
     /// and None otherwise
     pub fn get_u128<S: ToString>(&self, key: &S) -> Option<u128> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<u128>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an i128
@@ -152,10 +1092,7 @@ impl CapoConfig {
     /// An Option with the i128 value if the property can be represented as an
     /// i128 and None otherwise
     pub fn get_i128<S: ToString>(&self, key: &S) -> Option<i128> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<i128>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as a u64
@@ -167,10 +1104,7 @@ impl CapoConfig {
     /// An Option with the u64 value if the property can be represented as a u64
     /// and None otherwise
     pub fn get_u64<S: ToString>(&self, key: &S) -> Option<u64> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<u64>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an i64
@@ -182,10 +1116,7 @@ impl CapoConfig {
     /// An Option with the i64 value if the property can be represented as an
     /// i64 and None otherwise
     pub fn get_i64<S: ToString>(&self, key: &S) -> Option<i64> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<i64>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as a u32
@@ -197,10 +1128,7 @@ impl CapoConfig {
     /// An Option with the u32 value if the property can be represented as a u32
     /// and None otherwise
     pub fn get_u32<S: ToString>(&self, key: &S) -> Option<u32> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<u32>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an i32
@@ -212,10 +1140,7 @@ impl CapoConfig {
     /// An Option with the i32 value if the property can be represented as an
     /// i32 and None otherwise
     pub fn get_i32<S: ToString>(&self, key: &S) -> Option<i32> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<i32>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as a u16
@@ -227,10 +1152,7 @@ impl CapoConfig {
     /// An Option with the u16 value if the property can be represented as an
     /// u16 and None otherwise
     pub fn get_u16<S: ToString>(&self, key: &S) -> Option<u16> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<u16>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an i16
@@ -242,10 +1164,7 @@ impl CapoConfig {
     /// An Option with the i16 value if the property can be represented as an
     /// i16 and None otherwise
     pub fn get_i16<S: ToString>(&self, key: &S) -> Option<i16> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<i16>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as a u8
@@ -257,10 +1176,7 @@ impl CapoConfig {
     /// An Option with the u8 value if the property can be represented as a u8
     /// and None otherwise
     pub fn get_u8<S: ToString>(&self, key: &S) -> Option<u8> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<u8>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an i8
@@ -272,10 +1188,7 @@ impl CapoConfig {
     /// An Option with the i8 value if the property can be represented as an i8
     /// and None otherwise
     pub fn get_i8<S: ToString>(&self, key: &S) -> Option<i8> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<i8>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an f64
@@ -287,10 +1200,7 @@ impl CapoConfig {
     /// An Option with the f64 value if the property can be represented as an
     /// f64 and None otherwise
     pub fn get_f64<S: ToString>(&self, key: &S) -> Option<f64> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<f64>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an f32
@@ -302,10 +1212,7 @@ impl CapoConfig {
     /// An Option with the f32 value if the property can be represented as an
     /// f32 and None otherwise
     pub fn get_f32<S: ToString>(&self, key: &S) -> Option<f32> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<f32>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as a usize
@@ -317,10 +1224,7 @@ impl CapoConfig {
     /// An Option with the usize value if the property can be represented as a
     /// usize and None otherwise
     pub fn get_usize<S: ToString>(&self, key: &S) -> Option<usize> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<usize>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as an isize
@@ -332,10 +1236,7 @@ impl CapoConfig {
     /// An Option with the isize value if the property can be represented as an
     /// isize and None otherwise
     pub fn get_isize<S: ToString>(&self, key: &S) -> Option<isize> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => val.parse::<isize>().ok(),
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get the value of a CAPO property as a bool
@@ -347,14 +1248,7 @@ impl CapoConfig {
     /// An Option with the bool value if the property can be represented as a
     /// bool and None otherwise
     pub fn get_bool<S: ToString>(&self, key: &S) -> Option<bool> {
-        match self.options.get(&key.to_string().to_uppercase()) {
-            Some(val) => match val.to_lowercase().as_str() {
-                "yes" | "true" => Some(true),
-                "no" | "false" => Some(false),
-                _ => None,
-            },
-            None => None
-        }
+        self.get_as(key).ok().flatten()
     }
 
     /// Get all the options in the CAPO config
@@ -374,10 +1268,23 @@ impl CapoConfig {
     /// An Option with the location as a String or None if the property
     /// isn't found in any location
     pub fn get_location<S: ToString>(&self, key: &S) -> Option<String> {
-        match self.locations.get(&key.to_string().to_uppercase()) {
-            Some(v) => Some(v.clone()),
-            None => None,
-        }
+        self.locations.get(self.keys.get(&key.to_string().to_uppercase())?)
+            .cloned()
+    }
+
+    /// Get where the value of a CAPO property that is used was given, with
+    /// the full path of its file
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// An Option with the source as a String or None if the property isn't
+    /// found
+    pub fn get_source<S: ToString>(&self, key: &S) -> Option<String> {
+        self.history.get(self.keys.get(&key.to_string().to_uppercase())?)?
+            .last()
+            .map(|e| e.source())
     }
 
     /// Get all the locations in the CAPO config
@@ -388,4 +1295,109 @@ impl CapoConfig {
     pub fn get_locations(&self) -> HashMap<String, String> {
         self.locations.clone()
     }
+
+    /// Get every value a CAPO property was given, from the first file that
+    /// set it to the file whose value is used
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// An Option with a `Vec<CapoHistoryEntry>` in the order the files were
+    /// read or None if the property isn't found in any location
+    pub fn get_history<S: ToString>(&self, key: &S)
+        -> Option<Vec<CapoHistoryEntry>> {
+
+        self.history.get(self.keys.get(&key.to_string().to_uppercase())?)
+            .cloned()
+    }
+
+    /// Get the profile whose file gave a CAPO property the value that is used
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    ///
+    /// # Return
+    /// An Option with the profile as a String or None if the property isn't
+    /// found or its value came from an explicit source
+    pub fn get_profile<S: ToString>(&self, key: &S) -> Option<String> {
+        self.history.get(self.keys.get(&key.to_string().to_uppercase())?)?
+            .last()?
+            .profile
+            .clone()
+    }
+
+    /// Get the key of a CAPO property as it was written in the properties
+    /// files
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value in any case
+    ///
+    /// # Return
+    /// An Option with the key as a String or None if the property isn't found
+    pub fn get_key<S: ToString>(&self, key: &S) -> Option<String> {
+        self.keys.get(&key.to_string().to_uppercase()).cloned()
+    }
+}
+
+/// Parse a CAPO property value as a bool
+///
+/// # Arguments
+/// * `val` - A &str with the property value
+///
+/// # Return
+/// An Option with true for "yes" or "true", false for "no" or "false", and
+/// None otherwise, ignoring case
+pub(crate) fn parse_bool(val: &str) -> Option<bool> {
+    match val.to_lowercase().as_str() {
+        "yes" | "true" => Some(true),
+        "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a CAPO property value as any type implementing `FromStr`. A value
+/// `parse_bool` reads is tried again spelled "true" or "false", so a bool
+/// accepts "yes" and "no" in any case like every other getter
+///
+/// # Arguments
+/// * `val` - A &str with the property value
+///
+/// # Return
+/// A Result with the parsed value or the reason it can't be parsed
+pub(crate) fn parse_value<T>(val: &str) -> Result<T, String>
+    where T: FromStr, T::Err: fmt::Display {
+
+    val.parse::<T>().or_else(|e| match parse_bool(val) {
+        Some(b) => b.to_string().parse::<T>().map_err(|_| e.to_string()),
+        None => Err(e.to_string()),
+    })
+}
+
+/// Get the environment variable name a CAPO property key is overridden by,
+/// without the prefix
+///
+/// # Arguments
+/// * `key` - A &str with the property key
+///
+/// # Return
+/// A String with the key in uppercase, `__` for every dot and `_` for every
+/// dash
+fn env_name(key: &str) -> String {
+    key.to_uppercase().replace('.', "__").replace('-', "_")
+}
+
+/// Get the name of a type without the path of the module it's in
+///
+/// # Return
+/// A String like "u16" or "String"
+fn short_type_name<T>() -> String {
+    let name = any::type_name::<T>();
+
+    // Only strip the path before the outermost type
+    let outer = name.split('<').next().unwrap_or(name);
+    match outer.rfind("::") {
+        Some(i) => name[i + 2..].to_string(),
+        None => name.to_string(),
+    }
 }
@@ -2,65 +2,177 @@
 
 use std::{
     collections::HashMap,
-    fs::File,
+    fs::{
+        self,
+        File,
+    },
     io::BufReader,
     path::{
         Path,
         PathBuf,
     },
+    time::SystemTime,
+};
+
+use super::source::{
+    ConfigSource,
+    PropertiesSource,
 };
+use crate::helpers::errors::CapoError;
+
+/// The key of the directive naming the profiles a properties file extends
+pub(crate) const EXTENDS_KEY: &str = "capo.profile.extends";
 
-/// The struct for containing information from the required properties file
+/// The key of the directive naming the files a properties file includes
+pub(crate) const INCLUDE_KEY: &str = "capo.include";
+
+/// The struct for containing information from the required properties file.
+/// Directives like `capo.profile.extends` and `capo.include` are kept apart
+/// from the options
+#[derive(Clone)]
 pub struct CapoConfigurationFile {
     pub filename: String,
-    _path_to_file: PathBuf,
-    _last_read: i32,
+    pub path_to_file: PathBuf,
+    pub last_modified: Option<SystemTime>,
     pub options: HashMap<String, String>,
+    pub profile: Option<String>,
+    pub extends: Vec<String>,
+    pub includes: Vec<String>,
 }
 
 impl CapoConfigurationFile {
-    /// Collect the properties of a CAPO configuration file and return them in
-    /// a CapoConfigurationFile struct
+    /// Collect the properties of a properties file at an explicit location and
+    /// return them in a CapoConfigurationFile struct
     ///
     /// # Arguments
-    /// * `profile` - A ToString with the name of the profile
-    /// * `path` - An `impl AsRef<Path>` type with the path to the config file
+    /// * `path_to_file` - An `impl AsRef<Path>` type with the path to the
+    ///   properties file itself
     ///
     /// # Return
-    /// An Option containing a CapoConfigurationFile struct with the
-    /// configuration file properties or None if they couldn't be read
-    pub fn new<S: ToString>(profile: S, path: impl AsRef<Path>)
-        -> Option<Self> {
-
-        let mut filename = profile.to_string();
-        filename.push_str(".properties");
-
-        let mut path_to_file: PathBuf = path.as_ref().to_owned();
-        path_to_file.push(filename.clone());
-        let last_read = 0;
-
-        let config_file = match File::open(&path_to_file).ok() {
-            Some(c) => c,
-            None => {
-                eprintln!("WARNING: Couldn't open file: {:?}", path_to_file);
-                return None;
-            }
+    /// A Result containing a CapoConfigurationFile struct with the
+    /// configuration file properties or a CapoError if they couldn't be read
+    pub fn from_file(path_to_file: impl AsRef<Path>)
+        -> Result<Self, CapoError> {
+
+        Self::from_source(path_to_file, &PropertiesSource)
+    }
+
+    /// Collect the properties of a file in any format and return them in a
+    /// CapoConfigurationFile struct
+    ///
+    /// # Arguments
+    /// * `path_to_file` - An `impl AsRef<Path>` type with the path to the
+    ///   file itself
+    /// * `source` - The ConfigSource for the file's format
+    ///
+    /// # Return
+    /// A Result containing a CapoConfigurationFile struct with the flattened
+    /// properties or a CapoError if they couldn't be read
+    pub fn from_source(path_to_file: impl AsRef<Path>,
+        source: &dyn ConfigSource) -> Result<Self, CapoError> {
+
+        let path_to_file: PathBuf = path_to_file.as_ref().to_owned();
+        let filename = match path_to_file.file_name() {
+            Some(f) => f.to_string_lossy().to_string(),
+            None => path_to_file.display().to_string(),
         };
+        let config_file = match File::open(&path_to_file) {
+            Ok(c) => c,
+            Err(source) => return Err(CapoError::Io {
+                file: path_to_file,
+                source,
+            }),
+        };
+
+        // Note when the file was modified so changes to it can be detected
+        let last_modified = config_file.metadata()
+            .and_then(|m| m.modified())
+            .ok();
 
         // Read the options into the hashmap or die trying
-        let options = match java_properties::read(BufReader::new(config_file)){
-            Ok(o) => o,
-            Err(e) => {
-                eprintln!("WARNING: Couldn't read properties from file: {e}");
-                return None;
-            },
-        };
+        let mut options = source.read(&mut BufReader::new(config_file),
+            &path_to_file)?;
+
+        // Directives aren't properties themselves
+        let (mut extends, mut includes) = (Vec::new(), Vec::new());
+        options.retain(|key, val| {
+            let directive = match key {
+                k if k.eq_ignore_ascii_case(EXTENDS_KEY) => &mut extends,
+                k if k.eq_ignore_ascii_case(INCLUDE_KEY) => &mut includes,
+                _ => return true,
+            };
 
-        Some(Self {
+            directive.extend(val.split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty()));
+            false
+        });
+
+        Ok(Self {
             filename,
-            _path_to_file: path_to_file,
-            _last_read: last_read,
-            options
+            path_to_file,
+            last_modified,
+            options,
+            profile: None,
+            extends,
+            includes,
         })
     }
+
+    /// Find the files a properties file includes. Relative paths are relative
+    /// to the including file's directory, and glob patterns are expanded to
+    /// the files they match in alphabetical order
+    ///
+    /// # Return
+    /// A Result with a `Vec<PathBuf>` of the included files in the order
+    /// they're read or a CapoError if a pattern isn't valid
+    pub fn include_files(&self) -> Result<Vec<PathBuf>, CapoError> {
+        let dir = self.path_to_file.parent().unwrap_or(Path::new(""));
+        let mut files = Vec::new();
+
+        for include in &self.includes {
+            let path = dir.join(include);
+            if !include.contains(['*', '?', '[']) {
+                files.push(path);
+                continue;
+            }
+
+            let invalid = |reason: String| CapoError::Include {
+                file: self.path_to_file.clone(),
+                include: include.clone(),
+                reason,
+            };
+
+            // Only the include is a pattern, so a directory named with glob
+            // characters is matched as written
+            let pattern = match Path::new(include).is_absolute() {
+                true => include.clone(),
+                false => {
+                    let dir = glob::Pattern::escape(&dir.to_string_lossy());
+                    Path::new(&dir).join(include).to_string_lossy()
+                        .into_owned()
+                },
+            };
+
+            let matches = glob::glob(&pattern)
+                .map_err(|e| invalid(e.to_string()))?;
+            for matched in matches {
+                files.push(matched.map_err(|e| invalid(e.to_string()))?);
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+/// Get the time a file was last modified
+///
+/// # Arguments
+/// * `path` - An `impl AsRef<Path>` type with the path to the file
+///
+/// # Return
+/// An Option with the modification time or None if the file doesn't exist or
+/// the time isn't available
+pub(crate) fn modified_time(path: impl AsRef<Path>) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
 }
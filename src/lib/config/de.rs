@@ -0,0 +1,642 @@
+//! This file contains the deserialization of CAPO properties into types that
+//! implement serde's `Deserialize`.
+//!
+//! The dotted keys under a prefix are treated as a tree, so with the prefix
+//! `section2` the key `section2.programA.run` is the `run` field of the
+//! `programA` field. Field and variant names are matched ignoring case,
+//! underscores and dashes, so `contactList` fills a `contact_list` field. Lists
+//! are read from numbered keys like `hosts.0` and `hosts.1`, or from a single
+//! comma separated value.
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    str::FromStr,
+};
+
+use serde::de::{
+    self,
+    value::StringDeserializer,
+    DeserializeOwned,
+    DeserializeSeed,
+    EnumAccess,
+    IntoDeserializer,
+    MapAccess,
+    SeqAccess,
+    VariantAccess,
+    Visitor,
+};
+
+use super::config::{
+    parse_bool,
+    CapoConfig,
+    CapoHistoryEntry,
+};
+use crate::helpers::{
+    errors::CapoError,
+    keys::strip_prefix_ignoring_case,
+};
+
+/// The error for CAPO properties that can't be deserialized, with the key and
+/// location of the property that caused it when there is one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapoDeError {
+    pub key: Option<String>,
+    pub location: Option<String>,
+    pub message: String,
+}
+
+impl CapoDeError {
+    /// Attach a key and location to an error that doesn't have one yet
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the key being deserialized
+    /// * `entry` - An `Option<&CapoHistoryEntry>` with where the key's value
+    ///   was given
+    ///
+    /// # Return
+    /// The CapoDeError with its key and location filled in
+    fn at(mut self, key: &str, entry: Option<&CapoHistoryEntry>) -> Self {
+        if self.key.is_none() {
+            self.key = Some(key.to_string());
+            self.location = entry.map(|e| e.source());
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for CapoDeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.key, &self.location) {
+            (Some(k), Some(l)) => write!(f, "{} in {}: {}", k, l,
+                self.message),
+            (Some(k), None) => write!(f, "{}: {}", k, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Error for CapoDeError {}
+
+impl de::Error for CapoDeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self {
+            key: None,
+            location: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl CapoConfig {
+    /// Deserialize the properties under a key prefix into a type
+    ///
+    /// # Arguments
+    /// * `prefix` - A &str with the dotted key prefix, or an empty &str for
+    ///   every property
+    ///
+    /// # Return
+    /// A Result with the deserialized value or a CapoDeError naming the key
+    /// that couldn't be deserialized
+    pub fn deserialize<T: DeserializeOwned>(&self, prefix: &str)
+        -> Result<T, CapoDeError> {
+
+        let prefix = prefix.trim_end_matches('.').to_string();
+        let root = Node::build(self, &prefix);
+
+        T::deserialize(NodeDeserializer::new(&root, prefix))
+    }
+}
+
+/// A property value, where it was given and why its references couldn't be
+/// resolved if they couldn't
+#[derive(Clone, Copy)]
+struct Leaf<'a> {
+    value: &'a str,
+    entry: Option<&'a CapoHistoryEntry>,
+    unresolved: Option<&'a CapoError>,
+}
+
+/// A key in the tree of dotted keys, which may have a value, keys below it, or
+/// both
+#[derive(Default)]
+struct Node<'a> {
+    leaf: Option<Leaf<'a>>,
+    children: BTreeMap<String, Node<'a>>,
+}
+
+impl<'a> Node<'a> {
+    /// Build the tree of the properties under a prefix
+    ///
+    /// # Arguments
+    /// * `config` - The CapoConfig holding the properties
+    /// * `prefix` - A &str with the key prefix in any case
+    ///
+    /// # Return
+    /// The Node for the prefix itself
+    fn build(config: &'a CapoConfig, prefix: &str) -> Self {
+        let mut root = Self::default();
+
+        for (key, value) in &config.options {
+            // The prefix matches keys written in any case
+            let rest = match (prefix.is_empty(),
+                strip_prefix_ignoring_case(key, prefix)) {
+
+                (true, _) => Some(key.as_str()),
+                (false, Some("")) => Some(""),
+                (false, Some(r)) => r.strip_prefix('.'),
+                (false, None) => None,
+            };
+
+            let node = match rest {
+                Some("") => &mut root,
+                Some(r) => r.split('.').fold(&mut root, |node, segment| {
+                    node.children.entry(segment.to_string()).or_default()
+                }),
+                None => continue,
+            };
+
+            node.leaf = Some(Leaf {
+                value,
+                entry: config.history.get(key).and_then(|h| h.last()),
+                unresolved: config.unresolved.get(key),
+            });
+        }
+
+        root
+    }
+}
+
+/// Deserializes a single key of the tree, or a single item of a comma
+/// separated list
+struct NodeDeserializer<'a, 'b> {
+    leaf: Option<Leaf<'a>>,
+    children: Option<&'b BTreeMap<String, Node<'a>>>,
+    key: String,
+}
+
+impl<'a, 'b> NodeDeserializer<'a, 'b> {
+    /// Create a NodeDeserializer for a key of the tree
+    ///
+    /// # Arguments
+    /// * `node` - The Node of the key
+    /// * `key` - A String with the full key
+    ///
+    /// # Return
+    /// The NodeDeserializer for the key
+    fn new(node: &'b Node<'a>, key: String) -> Self {
+        Self {
+            leaf: node.leaf,
+            children: match node.children.is_empty() {
+                true => None,
+                false => Some(&node.children),
+            },
+            key,
+        }
+    }
+
+    /// Get the full key of a key below this one
+    ///
+    /// # Arguments
+    /// * `segment` - A &str with the last part of the key below
+    ///
+    /// # Return
+    /// A String with the full key
+    fn child_key(&self, segment: &str) -> String {
+        match self.key.is_empty() {
+            true => segment.to_string(),
+            false => format!("{}.{}", self.key, segment),
+        }
+    }
+
+    /// Create an error for this key
+    ///
+    /// # Arguments
+    /// * `message` - A ToString with what went wrong
+    ///
+    /// # Return
+    /// A CapoDeError with this key and its location
+    fn error<S: ToString>(&self, message: S) -> CapoDeError {
+        CapoDeError {
+            key: Some(self.key.clone()),
+            location: self.leaf
+                .and_then(|l| l.entry)
+                .map(|e| e.source()),
+            message: message.to_string(),
+        }
+    }
+
+    /// Attach this key to an error raised by a visitor if it doesn't name a
+    /// key already
+    ///
+    /// # Arguments
+    /// * `result` - The Result returned by the visitor
+    ///
+    /// # Return
+    /// The Result with the key attached to its error
+    fn at<T>(&self, result: Result<T, CapoDeError>) -> Result<T, CapoDeError> {
+        result.map_err(|e| e.at(&self.key,
+            self.leaf.and_then(|l| l.entry)))
+    }
+
+    /// Get the value of this key
+    ///
+    /// # Return
+    /// A Result with the Leaf or a CapoDeError if the key has no value or its
+    /// references couldn't be resolved
+    fn leaf(&self) -> Result<Leaf<'a>, CapoDeError> {
+        match self.leaf {
+            Some(Leaf { unresolved: Some(e), .. }) => Err(self.error(e)),
+            Some(leaf) => Ok(leaf),
+            None => Err(self.error("expected a value, found a section")),
+        }
+    }
+
+    /// Parse the value of this key
+    ///
+    /// # Arguments
+    /// * `type_name` - A &str with the name of the type for the error message
+    ///
+    /// # Return
+    /// A Result with the parsed value or a CapoDeError if it can't be parsed
+    fn parse<T: FromStr>(&self, type_name: &str) -> Result<T, CapoDeError> {
+        let value = self.leaf()?.value;
+        value.trim().parse::<T>().map_err(|_| self.error(
+            format!("invalid value '{}' for {}", value, type_name)))
+    }
+
+    /// Get the keys below this one as the items of a list. Numbered keys are
+    /// used in order, otherwise the value is split on commas
+    ///
+    /// # Return
+    /// A Result with the NodeDeserializer of each item or a CapoDeError if a
+    /// key below this one isn't a number
+    fn items(&self) -> Result<Vec<NodeDeserializer<'a, 'b>>, CapoDeError> {
+        if let Some(children) = self.children {
+            let mut items = Vec::new();
+            for (segment, node) in children {
+                let index = segment.parse::<usize>().map_err(|_| self.error(
+                    format!("'{}' isn't a list index", segment)))?;
+                items.push((index, Self::new(node, self.child_key(segment))));
+            }
+
+            items.sort_by_key(|(index, _)| *index);
+            return Ok(items.into_iter().map(|(_, item)| item).collect());
+        }
+
+        let leaf = self.leaf()?;
+        if leaf.value.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(leaf.value.split(',').map(|value| Self {
+            leaf: Some(Leaf {
+                value: value.trim(),
+                ..leaf
+            }),
+            children: None,
+            key: self.key.clone(),
+        }).collect())
+    }
+
+    /// Get the keys below this one as the entries of a map, renaming each to
+    /// the field it matches when deserializing a struct
+    ///
+    /// # Arguments
+    /// * `fields` - A slice of the struct's field names, empty for a map
+    ///
+    /// # Return
+    /// A Result with the name and NodeDeserializer of each entry or a
+    /// CapoDeError if this key has no keys below it
+    fn entries(&self, fields: &[&str])
+        -> Result<Vec<(String, NodeDeserializer<'a, 'b>)>, CapoDeError> {
+
+        let children = match (self.children, self.leaf) {
+            (Some(c), _) => c,
+            (None, Some(_)) =>
+                return Err(self.error("expected a section, found a value")),
+            (None, None) => return Ok(Vec::new()),
+        };
+
+        Ok(children.iter().map(|(segment, node)| {
+            let name = fields.iter()
+                .find(|f| same_name(f, segment))
+                .map(|f| f.to_string())
+                .unwrap_or_else(|| segment.clone());
+
+            (name, Self::new(node, self.child_key(segment)))
+        }).collect())
+    }
+}
+
+/// Check whether a key segment names a field or variant
+///
+/// # Arguments
+/// * `name` - A &str with the field or variant name
+/// * `segment` - A &str with the key segment
+///
+/// # Return
+/// A bool that is true if they're the same ignoring case, underscores and
+/// dashes
+fn same_name(name: &str, segment: &str) -> bool {
+    let normalize = |s: &str| s.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(|c| c.to_lowercase())
+        .collect::<String>();
+
+    normalize(name) == normalize(segment)
+}
+
+/// Implement the deserialization of a type parsed from a single value
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident: $type:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V)
+                -> Result<V::Value, Self::Error> {
+
+                let value = self.parse::<$type>(stringify!($type))?;
+                self.at(visitor.$visit(value))
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for NodeDeserializer<'_, '_> {
+    type Error = CapoDeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        match self.children {
+            Some(_) => self.deserialize_map(visitor),
+            None => self.deserialize_str(visitor),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+        deserialize_char => visit_char: char,
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        let value = self.leaf()?.value;
+        match parse_bool(value.trim()) {
+            Some(b) => self.at(visitor.visit_bool(b)),
+            None => Err(self.error(
+                format!("invalid value '{}' for bool", value))),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        let value = self.leaf()?.value;
+        self.at(visitor.visit_str(value))
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        let value = self.leaf()?.value;
+        self.at(visitor.visit_bytes(value.as_bytes()))
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        match (self.leaf, self.children) {
+            (None, None) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        self.at(visitor.visit_unit())
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str,
+        visitor: V) -> Result<V::Value, Self::Error> {
+
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str,
+        visitor: V) -> Result<V::Value, Self::Error> {
+
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        let items = self.items()?;
+        self.at(visitor.visit_seq(ItemAccess {
+            items: items.into_iter(),
+        }))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str,
+        _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        let entries = self.entries(&[])?;
+        self.at(visitor.visit_map(EntryAccess {
+            entries: entries.into_iter(),
+            value: None,
+        }))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str,
+        fields: &'static [&'static str], visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        let entries = self.entries(fields)?;
+        self.at(visitor.visit_map(EntryAccess {
+            entries: entries.into_iter(),
+            value: None,
+        }))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str,
+        variants: &'static [&'static str], visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        let variant_name = |name: &str| variants.iter()
+            .find(|v| same_name(v, name))
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| name.to_string());
+
+        // A value names a unit variant, a single key below this one names a
+        // variant holding the keys below that
+        match self.children.map(|c| c.iter().collect::<Vec<_>>()) {
+            None => {
+                let name = variant_name(self.leaf()?.value.trim());
+                let variant: StringDeserializer<CapoDeError> =
+                    name.into_deserializer();
+                self.at(visitor.visit_enum(variant))
+            },
+            Some(children) if children.len() == 1 => {
+                let (segment, node) = children[0];
+                let variant = VariantNode {
+                    name: variant_name(segment),
+                    content: Self::new(node, self.child_key(segment)),
+                };
+                self.at(visitor.visit_enum(variant))
+            },
+            Some(_) => Err(self.error(
+                "expected a single variant, found several keys")),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        visitor.visit_unit()
+    }
+}
+
+/// Gives the items of a list to a visitor
+struct ItemAccess<'a, 'b> {
+    items: std::vec::IntoIter<NodeDeserializer<'a, 'b>>,
+}
+
+impl<'de> SeqAccess<'de> for ItemAccess<'_, '_> {
+    type Error = CapoDeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T)
+        -> Result<Option<T::Value>, Self::Error> {
+
+        match self.items.next() {
+            Some(item) => seed.deserialize(item).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Gives the entries of a map or struct to a visitor
+struct EntryAccess<'a, 'b> {
+    entries: std::vec::IntoIter<(String, NodeDeserializer<'a, 'b>)>,
+    value: Option<NodeDeserializer<'a, 'b>>,
+}
+
+impl<'de> MapAccess<'de> for EntryAccess<'_, '_> {
+    type Error = CapoDeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K)
+        -> Result<Option<K::Value>, Self::Error> {
+
+        match self.entries.next() {
+            Some((name, value)) => {
+                self.value = Some(value);
+                let name: StringDeserializer<CapoDeError> =
+                    name.into_deserializer();
+                seed.deserialize(name).map(Some)
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V)
+        -> Result<V::Value, Self::Error> {
+
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::custom("value requested before its key")),
+        }
+    }
+}
+
+/// Gives an enum variant named by a key, and the keys below it, to a visitor
+struct VariantNode<'a, 'b> {
+    name: String,
+    content: NodeDeserializer<'a, 'b>,
+}
+
+impl<'de, 'a, 'b> EnumAccess<'de> for VariantNode<'a, 'b> {
+    type Error = CapoDeError;
+    type Variant = NodeDeserializer<'a, 'b>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V)
+        -> Result<(V::Value, Self::Variant), Self::Error> {
+
+        let name: StringDeserializer<CapoDeError> =
+            self.name.into_deserializer();
+        Ok((seed.deserialize(name)?, self.content))
+    }
+}
+
+impl<'de> VariantAccess<'de> for NodeDeserializer<'_, '_> {
+    type Error = CapoDeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T)
+        -> Result<T::Value, Self::Error> {
+
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self,
+        fields: &'static [&'static str], visitor: V)
+        -> Result<V::Value, Self::Error> {
+
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
@@ -0,0 +1,140 @@
+//! This file contains the structs describing how two CAPO configurations differ
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
+
+use super::config::CapoConfig;
+
+/// How a single CAPO property differs between two configurations. Added
+/// properties have no old value or location and removed properties have no new
+/// value or location
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapoKeyChange {
+    pub key: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub old_location: Option<String>,
+    pub new_location: Option<String>,
+}
+
+/// The properties that were added, removed or given a different value between
+/// two configurations, each sorted by key
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    pub added: Vec<CapoKeyChange>,
+    pub removed: Vec<CapoKeyChange>,
+    pub changed: Vec<CapoKeyChange>,
+}
+
+impl ConfigDiff {
+    /// Compare the properties and locations of two configurations
+    ///
+    /// # Arguments
+    /// * `old_options` - The properties of the old configuration
+    /// * `old_locations` - The locations of the old configuration's properties
+    /// * `new_options` - The properties of the new configuration
+    /// * `new_locations` - The locations of the new configuration's properties
+    ///
+    /// # Return
+    /// A ConfigDiff with every property whose value differs. Keys are matched
+    /// in any case and named the way the new configuration spells them
+    pub(crate) fn from_maps(old_options: &HashMap<String, String>,
+        old_locations: &HashMap<String, String>,
+        new_options: &HashMap<String, String>,
+        new_locations: &HashMap<String, String>) -> Self {
+
+        let mut diff = Self::default();
+        let old_keys = keys_by_case(old_options);
+        let new_keys = keys_by_case(new_options);
+        let keys: BTreeSet<&String> = old_keys.keys()
+            .chain(new_keys.keys())
+            .collect();
+
+        for upper in keys {
+            let (old_key, new_key) = (old_keys.get(upper), new_keys.get(upper));
+            let key = match new_key.or(old_key) {
+                Some(k) => k.to_string(),
+                None => continue,
+            };
+
+            let change = CapoKeyChange {
+                key,
+                old_value: old_key.and_then(|k| old_options.get(*k)).cloned(),
+                new_value: new_key.and_then(|k| new_options.get(*k)).cloned(),
+                old_location: old_key.and_then(|k| old_locations.get(*k))
+                    .cloned(),
+                new_location: new_key.and_then(|k| new_locations.get(*k))
+                    .cloned(),
+            };
+
+            match (&change.old_value, &change.new_value) {
+                (None, Some(_)) => diff.added.push(change),
+                (Some(_), None) => diff.removed.push(change),
+                (Some(o), Some(n)) if o != n => diff.changed.push(change),
+                _ => continue,
+            };
+        }
+
+        diff
+    }
+
+    /// Check whether the two configurations have the same properties
+    ///
+    /// # Return
+    /// A bool that is true if nothing was added, removed or changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+
+    /// Get every added, removed and changed property
+    ///
+    /// # Return
+    /// An Iterator over the added, then removed, then changed properties
+    pub fn changes(&self) -> impl Iterator<Item = &CapoKeyChange> {
+        self.added.iter()
+            .chain(self.removed.iter())
+            .chain(self.changed.iter())
+    }
+
+    /// Get how a single property differs
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value in any case
+    ///
+    /// # Return
+    /// An Option with the CapoKeyChange or None if the property doesn't differ
+    pub fn get<S: ToString>(&self, key: &S) -> Option<&CapoKeyChange> {
+        let key = key.to_string().to_uppercase();
+        self.changes().find(|c| c.key.to_uppercase() == key)
+    }
+}
+
+impl CapoConfig {
+    /// Compare this configuration with another, like the same path read with
+    /// two profiles or the same profile read from two paths
+    ///
+    /// # Arguments
+    /// * `other` - The CapoConfig to compare with, taken as the new one
+    ///
+    /// # Return
+    /// A ConfigDiff with the properties other adds, removes or changes
+    pub fn diff(&self, other: &CapoConfig) -> ConfigDiff {
+        ConfigDiff::from_maps(&self.options, &self.locations, &other.options,
+            &other.locations)
+    }
+}
+
+/// Index the keys of a map by their upper case spelling
+///
+/// # Arguments
+/// * `options` - The properties of a configuration
+///
+/// # Return
+/// A HashMap from each upper case key to the key as the files spell it
+fn keys_by_case(options: &HashMap<String, String>) -> HashMap<String, &String> {
+    options.keys()
+        .map(|k| (k.to_uppercase(), k))
+        .collect()
+}
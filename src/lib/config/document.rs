@@ -0,0 +1,740 @@
+//! This file contains a lossless model of a `.properties` file that can be
+//! edited and written back.
+//!
+//! `java_properties` reads a file into a map, losing its comments, blank
+//! lines, separators, continuation lines and escapes. A PropertiesDocument
+//! keeps every line as it was written and only rewrites the lines of the
+//! settings that are changed, so an untouched line is written back byte for
+//! byte.
+//!
+//! Files are read as ISO-8859-1 like `java_properties` reads them, so every
+//! byte read is the byte written back.
+use std::{
+    fmt,
+    fs,
+    io::Write,
+    path::Path,
+};
+
+use crate::helpers::errors::CapoError;
+
+/// The separator given to settings added to a document without any
+const DEFAULT_SEPARATOR: &str = " = ";
+
+/// A logical line of a properties file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PropertiesLine {
+    /// A line with nothing but whitespace
+    Blank(String),
+
+    /// A line starting with `#` or `!`
+    Comment(String),
+
+    /// A setting, which may continue over several physical lines
+    Entry(PropertiesEntry),
+}
+
+/// A setting in a properties file, kept as the physical lines it was written
+/// on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertiesEntry {
+    physical: Vec<String>,
+    key: String,
+    key_end: (usize, usize),
+    value_start: (usize, usize),
+}
+
+/// A `.properties` file parsed into its lines. Each physical line keeps the
+/// terminator it was written with, `\n`, `\r\n`, `\r` or none at the end of
+/// the file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertiesDocument {
+    lines: Vec<PropertiesLine>,
+    endings: Vec<Vec<String>>,
+    newline: String,
+}
+
+impl Default for PropertiesDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PropertiesDocument {
+    /// Create an empty document
+    ///
+    /// # Return
+    /// A PropertiesDocument without any lines, ending lines with `\n`
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            endings: Vec::new(),
+            newline: String::from("\n"),
+        }
+    }
+
+    /// Parse the text of a properties file
+    ///
+    /// # Arguments
+    /// * `text` - A &str with the contents of the file
+    ///
+    /// # Return
+    /// A PropertiesDocument that writes back the same text. Lines added to it
+    /// end with the terminator most of its lines end with
+    pub fn parse(text: &str) -> Self {
+        let physical = split_lines(text);
+        let mut document = Self::new();
+        document.newline = dominant_ending(&physical).to_string();
+
+        let mut current: Vec<String> = Vec::new();
+        let mut endings: Vec<String> = Vec::new();
+        for (line, ending) in physical {
+            // Comments and blank lines never continue onto the next line
+            if current.is_empty() && line.trim_matches(is_blank).is_empty() {
+                document.push(PropertiesLine::Blank(line.to_string()),
+                    vec![ending.to_string()]);
+                continue;
+            }
+            if current.is_empty() && is_comment(line) {
+                document.push(PropertiesLine::Comment(line.to_string()),
+                    vec![ending.to_string()]);
+                continue;
+            }
+
+            current.push(line.to_string());
+            endings.push(ending.to_string());
+            if !ends_in_escape(line) {
+                let physical = std::mem::take(&mut current);
+                document.push(PropertiesLine::Entry(PropertiesEntry::new(
+                    physical)), std::mem::take(&mut endings));
+            }
+        }
+        if !current.is_empty() {
+            document.push(PropertiesLine::Entry(PropertiesEntry::new(current)),
+                endings);
+        }
+
+        document
+    }
+
+    /// Parse the bytes of a properties file as ISO-8859-1
+    ///
+    /// # Arguments
+    /// * `bytes` - A `&[u8]` with the contents of the file
+    ///
+    /// # Return
+    /// A PropertiesDocument whose `to_bytes` gives back the same bytes
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        // ISO-8859-1 maps every byte to the char with the same value
+        Self::parse(&bytes.iter().map(|&b| b as char).collect::<String>())
+    }
+
+    /// Read and parse a properties file
+    ///
+    /// # Arguments
+    /// * `file` - An `impl AsRef<Path>` with the path to the file
+    ///
+    /// # Return
+    /// A Result with the PropertiesDocument or a CapoError if the file
+    /// couldn't be read
+    pub fn from_file(file: impl AsRef<Path>) -> Result<Self, CapoError> {
+        match fs::read(file.as_ref()) {
+            Ok(bytes) => Ok(Self::from_bytes(&bytes)),
+            Err(source) => Err(CapoError::Io {
+                file: file.as_ref().to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    /// Get the lines of the document
+    ///
+    /// # Return
+    /// A slice of the PropertiesLines in the order they're written
+    pub fn lines(&self) -> &[PropertiesLine] {
+        &self.lines
+    }
+
+    /// Get the keys of the settings in the document
+    ///
+    /// # Return
+    /// A `Vec<String>` of the unescaped keys in the order they're written,
+    /// without duplicates
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        for entry in self.entries() {
+            if !keys.iter().any(|k| same_key(k, entry.key())) {
+                keys.push(entry.key().to_string());
+            }
+        }
+
+        keys
+    }
+
+    /// Get the value of a setting, from the last line setting its key like
+    /// `java_properties` reads it. Keys are matched ignoring case
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the setting's key
+    ///
+    /// # Return
+    /// An Option with the unescaped value or None if no line sets the key
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.entries()
+            .filter(|e| same_key(e.key(), key))
+            .last()
+            .map(|e| e.value())
+    }
+
+    /// Get the line a setting starts on, from the last line setting its key
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the setting's key
+    ///
+    /// # Return
+    /// An Option with the line number, counting from 1, or None if no line
+    /// sets the key
+    pub fn line_of(&self, key: &str) -> Option<usize> {
+        let mut number = 1;
+        let mut found = None;
+        for line in &self.lines {
+            match line {
+                PropertiesLine::Entry(e) => {
+                    if same_key(e.key(), key) {
+                        found = Some(number);
+                    }
+                    number += e.raw_lines().len();
+                },
+                _ => number += 1,
+            };
+        }
+
+        found
+    }
+
+    /// Check whether a line sets a key, ignoring case
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the setting's key
+    ///
+    /// # Return
+    /// A bool that is true if the key is set
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries().any(|e| same_key(e.key(), key))
+    }
+
+    /// Give a setting a value, updating it if it's set and adding it to the
+    /// end of the document otherwise. A new setting uses the separator of the
+    /// last setting in the document, and a backslash continuing the last line
+    /// past the end of the file is dropped first, as readers drop it, so the
+    /// new setting isn't read as part of that value
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the setting's key
+    /// * `value` - A &str with the unescaped value
+    ///
+    /// # Return
+    /// An Option with the value the setting had, or None if it's new
+    pub fn insert(&mut self, key: &str, value: &str) -> Option<String> {
+        let previous = self.get(key);
+        if !self.update(key, value) {
+            let separator = self.entries()
+                .last()
+                .map(|e| e.separator())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| DEFAULT_SEPARATOR.to_string());
+            let line = format!("{}{}{}", escape(key, true), separator,
+                escape(value, false));
+
+            self.close_last_line();
+            let ending = match self.endings.last_mut()
+                .and_then(|e| e.last_mut()) {
+                // The new line ends the file the way the last line did
+                Some(last) => std::mem::replace(last, self.newline.clone()),
+                None => self.newline.clone(),
+            };
+            self.push(PropertiesLine::Entry(PropertiesEntry::new(vec![line])),
+                vec![ending]);
+        }
+
+        previous
+    }
+
+    /// Change the value of a setting that's already in the document. Only
+    /// the text after the separator of the last line setting the key is
+    /// rewritten, so the key keeps its spelling and separator
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the setting's key
+    /// * `value` - A &str with the unescaped value
+    ///
+    /// # Return
+    /// A bool that is true if the setting was found and changed
+    pub fn update(&mut self, key: &str, value: &str) -> bool {
+        let found = self.lines.iter_mut()
+            .zip(self.endings.iter_mut())
+            .rev()
+            .find_map(|(l, endings)| match l {
+                PropertiesLine::Entry(e) if same_key(e.key(), key) =>
+                    Some((e, endings)),
+                _ => None,
+            });
+
+        match found {
+            Some((e, endings)) => {
+                // The lines joined into one keep the last one's terminator
+                e.set_value(value);
+                let last = endings.pop().unwrap_or_default();
+                endings.truncate(e.raw_lines().len() - 1);
+                endings.push(last);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Remove every line setting a key, ignoring case
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the setting's key
+    ///
+    /// # Return
+    /// An Option with the value the setting had, or None if it wasn't set
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let previous = self.get(key);
+        let last_ending = self.endings.last()
+            .and_then(|e| e.last())
+            .cloned();
+
+        let lines = std::mem::take(&mut self.lines);
+        let endings = std::mem::take(&mut self.endings);
+        for (line, endings) in lines.into_iter().zip(endings) {
+            if !matches!(&line,
+                PropertiesLine::Entry(e) if same_key(e.key(), key)) {
+                self.push(line, endings);
+            }
+        }
+
+        // The file still ends the way it did
+        if let (Some(ending), Some(last)) = (last_ending,
+            self.endings.last_mut().and_then(|e| e.last_mut())) {
+            *last = ending;
+        }
+
+        previous
+    }
+
+    /// Get the contents of the document as ISO-8859-1 bytes. A char that
+    /// ISO-8859-1 can't hold, which only a document parsed from a &str can
+    /// have, is written as a `\uXXXX` escape
+    ///
+    /// # Return
+    /// A `Vec<u8>` with the contents of the file
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for c in self.to_string().chars() {
+            match u8::try_from(c) {
+                Ok(b) => bytes.push(b),
+                Err(_) => bytes.extend(escape(&c.to_string(), false).bytes()),
+            };
+        }
+
+        bytes
+    }
+
+    /// Write the document to a writer as ISO-8859-1 bytes
+    ///
+    /// # Arguments
+    /// * `writer` - The `impl Write` to write to
+    ///
+    /// # Return
+    /// A Result with nothing or the io::Error the writer failed with
+    pub fn write_to(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Write the document to a file, replacing what's in it
+    ///
+    /// # Arguments
+    /// * `file` - An `impl AsRef<Path>` with the path to the file
+    ///
+    /// # Return
+    /// A Result with nothing or a CapoError if the file couldn't be written
+    pub fn save(&self, file: impl AsRef<Path>) -> Result<(), CapoError> {
+        fs::write(file.as_ref(), self.to_bytes()).map_err(|source|
+            CapoError::Io {
+                file: file.as_ref().to_path_buf(),
+                source,
+            })
+    }
+
+    /// Add a line to the end of the document
+    ///
+    /// # Arguments
+    /// * `line` - The PropertiesLine to add
+    /// * `endings` - The terminator of each of its physical lines
+    fn push(&mut self, line: PropertiesLine, endings: Vec<String>) {
+        self.lines.push(line);
+        self.endings.push(endings);
+    }
+
+    /// Drop the backslash of a last line that continues past the end of the
+    /// file, which readers leave out of the value, so a line can be added
+    /// after it
+    fn close_last_line(&mut self) {
+        if let Some(PropertiesLine::Entry(e)) = self.lines.last_mut() {
+            let mut physical = e.raw_lines().to_vec();
+            if let Some(last) = physical.last_mut()
+                .filter(|l| ends_in_escape(l)) {
+                last.pop();
+                *e = PropertiesEntry::new(physical);
+            }
+        }
+    }
+
+    /// Iterate over the settings in the document
+    fn entries(&self) -> impl Iterator<Item = &PropertiesEntry> {
+        self.lines.iter().filter_map(|l| match l {
+            PropertiesLine::Entry(e) => Some(e),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for PropertiesDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let physical = self.lines.iter().flat_map(|l| match l {
+            PropertiesLine::Blank(text) | PropertiesLine::Comment(text) =>
+                std::slice::from_ref(text),
+            PropertiesLine::Entry(e) => e.raw_lines(),
+        });
+
+        for (line, ending) in physical.zip(self.endings.iter().flatten()) {
+            f.write_str(line)?;
+            f.write_str(ending)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PropertiesEntry {
+    /// Parse the physical lines of a setting
+    ///
+    /// # Arguments
+    /// * `physical` - A `Vec<String>` of the lines without their line
+    ///   endings, each but the last ending in a backslash
+    ///
+    /// # Return
+    /// The PropertiesEntry with its key and where its value starts
+    fn new(physical: Vec<String>) -> Self {
+        let mut entry = Self {
+            physical,
+            key: String::new(),
+            key_end: (0, 0),
+            value_start: (0, 0),
+        };
+
+        let chars = entry.logical_chars();
+        let end = entry.end();
+        let position = |i: usize| chars.get(i).map(|c| (c.0, c.1))
+            .unwrap_or(end);
+
+        let mut i = chars.iter().take_while(|c| is_blank(c.2)).count();
+        while i < chars.len() {
+            match chars[i].2 {
+                '\\' => {
+                    let (c, len) = unescape(&chars[i + 1..]);
+                    entry.key.push(c);
+                    i += 1 + len;
+                    continue;
+                },
+                c if is_blank(c) || c == '=' || c == ':' => break,
+                c => entry.key.push(c),
+            };
+            i += 1;
+        }
+        entry.key_end = position(i);
+
+        // The separator is whitespace, = or : with whitespace around it
+        while i < chars.len() && is_blank(chars[i].2) {
+            i += 1;
+        }
+        if i < chars.len() && (chars[i].2 == '=' || chars[i].2 == ':') {
+            i += 1;
+        }
+        while i < chars.len() && is_blank(chars[i].2) {
+            i += 1;
+        }
+        entry.value_start = position(i);
+
+        entry
+    }
+
+    /// Get the key of the setting
+    ///
+    /// # Return
+    /// A &str with the unescaped key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Get the value of the setting
+    ///
+    /// # Return
+    /// A String with the unescaped value, its continuation lines joined
+    pub fn value(&self) -> String {
+        let chars = self.logical_chars();
+        let mut i = chars.iter()
+            .position(|c| (c.0, c.1) >= self.value_start)
+            .unwrap_or(chars.len());
+
+        let mut value = String::new();
+        while i < chars.len() {
+            match chars[i].2 {
+                '\\' => {
+                    let (c, len) = unescape(&chars[i + 1..]);
+                    value.push(c);
+                    i += 1 + len;
+                },
+                c => {
+                    value.push(c);
+                    i += 1;
+                },
+            };
+        }
+
+        value
+    }
+
+    /// Get the separator between the key and the value
+    ///
+    /// # Return
+    /// A String with the separator as it's written, like " = " or ":"
+    pub fn separator(&self) -> String {
+        self.logical_chars().iter()
+            .filter(|c| (c.0, c.1) >= self.key_end
+                && (c.0, c.1) < self.value_start)
+            .map(|c| c.2)
+            .collect()
+    }
+
+    /// Get the physical lines of the setting
+    ///
+    /// # Return
+    /// A slice of the lines as they're written, without line endings
+    pub fn raw_lines(&self) -> &[String] {
+        &self.physical
+    }
+
+    /// Rewrite the text after the separator, joining the setting onto one
+    /// line from the line its value started on. A key without a separator is
+    /// given the default one, so the value isn't read as part of the key
+    ///
+    /// # Arguments
+    /// * `value` - A &str with the unescaped value
+    fn set_value(&mut self, value: &str) {
+        let (index, offset) = self.value_start;
+        let mut line = self.physical[index][..offset].to_string();
+        if self.key_end == self.value_start {
+            line.push_str(DEFAULT_SEPARATOR);
+        }
+        line.push_str(&escape(value, false));
+
+        self.physical.truncate(index);
+        self.physical.push(line);
+        *self = Self::new(std::mem::take(&mut self.physical));
+    }
+
+    /// Get the position just past the last char of the setting, before the
+    /// backslash of a last line that continues past the end of the file
+    fn end(&self) -> (usize, usize) {
+        let last = self.physical.len() - 1;
+        let text = &self.physical[last];
+        match ends_in_escape(text) {
+            true => (last, text.len() - 1),
+            _ => (last, text.len()),
+        }
+    }
+
+    /// Get every char of the logical line with the physical line and byte
+    /// offset it's at, leaving out the backslashes and indentation that join
+    /// the physical lines. Like java.util.Properties, a backslash continuing
+    /// the last line past the end of the file is left out too
+    fn logical_chars(&self) -> Vec<(usize, usize, char)> {
+        let mut chars = Vec::new();
+        let last = self.physical.len() - 1;
+
+        for (i, text) in self.physical.iter().enumerate() {
+            let text = match i < last || ends_in_escape(text) {
+                true => &text[..text.len() - 1],
+                _ => text.as_str(),
+            };
+            let start = match i {
+                0 => 0,
+                _ => text.len() - text.trim_start_matches(is_blank).len(),
+            };
+
+            chars.extend(text[start..].char_indices()
+                .map(|(o, c)| (i, start + o, c)));
+        }
+
+        chars
+    }
+}
+
+/// Split text into its physical lines. Like java.util.Properties, a line
+/// ends at `\n`, `\r\n` or a lone `\r`
+///
+/// # Arguments
+/// * `text` - A &str with the contents of a file
+///
+/// # Return
+/// A `Vec<(&str, &str)>` of each line without its terminator and the
+/// terminator, which is empty for a last line without one
+fn split_lines(text: &str) -> Vec<(&str, &str)> {
+    let bytes = text.as_bytes();
+    let mut lines = Vec::new();
+    let (mut start, mut i) = (0, 0);
+
+    while i < bytes.len() {
+        let len = match (bytes[i], bytes.get(i + 1)) {
+            (b'\r', Some(b'\n')) => 2,
+            (b'\r' | b'\n', _) => 1,
+            _ => {
+                i += 1;
+                continue;
+            },
+        };
+
+        lines.push((&text[start..i], &text[i..i + len]));
+        i += len;
+        start = i;
+    }
+    if start < text.len() {
+        lines.push((&text[start..], ""));
+    }
+
+    lines
+}
+
+/// Find the terminator most lines end with, to end new lines with
+///
+/// # Arguments
+/// * `lines` - The physical lines with their terminators
+///
+/// # Return
+/// A &str with the terminator, `\n` if no line has one or on a tie
+fn dominant_ending(lines: &[(&str, &str)]) -> &'static str {
+    // On a tie the last is kept, so `\n` goes last
+    ["\r", "\r\n", "\n"].into_iter()
+        .max_by_key(|ending| lines.iter().filter(|l| l.1 == *ending).count())
+        .unwrap_or("\n")
+}
+
+/// Check whether two keys are the same setting, ignoring case
+fn same_key(first: &str, second: &str) -> bool {
+    first.to_uppercase() == second.to_uppercase()
+}
+
+/// Check whether a physical line is a comment
+fn is_comment(line: &str) -> bool {
+    line.trim_start_matches(is_blank).starts_with(['#', '!'])
+}
+
+/// Check whether a char is whitespace between the parts of a line
+fn is_blank(c: char) -> bool {
+    c == ' ' || c == '\t' || c == '\x0c'
+}
+
+/// Check whether a physical line ends in a backslash that isn't escaped, and
+/// so continues onto the next line
+fn ends_in_escape(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// Read the escape sequence after a backslash
+///
+/// # Arguments
+/// * `chars` - The chars after the backslash with their positions
+///
+/// # Return
+/// The char the sequence stands for and how many chars it takes up
+fn unescape(chars: &[(usize, usize, char)]) -> (char, usize) {
+    match chars.first().map(|c| c.2) {
+        Some('t') => ('\t', 1),
+        Some('n') => ('\n', 1),
+        Some('r') => ('\r', 1),
+        Some('f') => ('\x0c', 1),
+        Some('u') => match (hex_unit(&chars[1..]),
+            hex_unit(chars.get(7..).unwrap_or_default())) {
+            // A char outside the BMP is written as a UTF-16 surrogate pair
+            (Some(high), Some(low)) if (0xD800..0xDC00).contains(&high)
+                && chars.get(5).map(|c| c.2) == Some('\\')
+                && chars.get(6).map(|c| c.2) == Some('u') =>
+                match char::decode_utf16([high, low]).next() {
+                    Some(Ok(c)) => (c, 11),
+                    _ => ('u', 1),
+                },
+            (Some(unit), _) => match char::from_u32(unit.into()) {
+                Some(c) => (c, 5),
+                None => ('u', 1),
+            },
+            _ => ('u', 1),
+        },
+        Some(c) => (c, 1),
+        None => ('\\', 0),
+    }
+}
+
+/// Read the four hex digits of a `\u` escape
+///
+/// # Arguments
+/// * `chars` - The chars after the `u` with their positions
+///
+/// # Return
+/// An Option with the UTF-16 code unit or None if there aren't four hex
+/// digits
+fn hex_unit(chars: &[(usize, usize, char)]) -> Option<u16> {
+    let hex: String = chars.iter().take(4).map(|c| c.2).collect();
+    match hex.len() == 4 {
+        true => u16::from_str_radix(&hex, 16).ok(),
+        _ => None,
+    }
+}
+
+/// Escape a key or value so `java_properties` reads it back unchanged
+///
+/// # Arguments
+/// * `text` - A &str with the unescaped key or value
+/// * `is_key` - A bool that is true if the text is a key, whose separators
+///   and comment characters must be escaped too
+///
+/// # Return
+/// A String that only contains printable ASCII
+fn escape(text: &str, is_key: bool) -> String {
+    let mut escaped = String::new();
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\x0c' => escaped.push_str("\\f"),
+            ' ' if is_key || i == 0 => escaped.push_str("\\ "),
+            '=' | ':' | '#' | '!' if is_key => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            ' '..='~' => escaped.push(c),
+            c => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("\\u{:04X}", unit));
+                }
+            },
+        };
+    }
+
+    escaped
+}
@@ -0,0 +1,197 @@
+//! This file contains the resolution of references inside property values.
+//!
+//! A value can contain `${section.key}` to insert the value of another CAPO
+//! property, or `${env:NAME}` to insert the value of an environment variable.
+//! Writing `$${` inserts a literal `${` without resolving anything.
+//!
+//! A value with an undefined reference, or a reference that loops back on
+//! itself, is kept as it was written instead of failing the whole
+//! configuration. `CapoConfig::get` returns it that way, while the typed
+//! getters report why it couldn't be resolved when the key is read.
+use std::{
+    collections::HashMap,
+    env,
+};
+
+use crate::helpers::errors::CapoError;
+
+/// Prefix of a reference to an environment variable
+const ENV_PREFIX: &str = "env:";
+
+/// Resolve the references in every property value. A value with a reference
+/// that can't be resolved, or that depends on one, is kept as it was written
+/// so the rest of the configuration can still be used
+///
+/// # Arguments
+/// * `raw` - A HashMap of the property keys and their values as they were
+///   read from the files
+///
+/// # Return
+/// A tuple of a HashMap of the same keys and their resolved values, and a
+/// HashMap of the keys whose values couldn't be resolved and the CapoError
+/// saying why
+pub(crate) fn resolve_all(raw: &HashMap<String, String>)
+    -> (HashMap<String, String>, HashMap<String, CapoError>) {
+
+    let mut resolver = Resolver {
+        raw,
+        keys: raw.keys().map(|k| (k.to_uppercase(), k.clone())).collect(),
+        resolved: HashMap::new(),
+        failed: HashMap::new(),
+        stack: Vec::new(),
+    };
+
+    for key in raw.keys() {
+        // A failure is kept in `failed` for every key it reaches
+        let _ = resolver.resolve(key);
+    }
+
+    let failed = resolver.failed.into_iter()
+        .map(|(key, failure)| (key, failure.into()))
+        .collect();
+
+    (resolver.resolved, failed)
+}
+
+/// Why a value couldn't be resolved, kept for every key that depends on it
+#[derive(Clone)]
+enum Failure {
+    Cycle(Vec<String>),
+    Undefined {
+        key: String,
+        reference: String,
+    },
+}
+
+impl From<Failure> for CapoError {
+    fn from(failure: Failure) -> Self {
+        match failure {
+            Failure::Cycle(chain) => CapoError::InterpolationCycle { chain },
+            Failure::Undefined { key, reference } =>
+                CapoError::UnresolvedReference { key, reference },
+        }
+    }
+}
+
+/// Tracks the values resolved so far and the chain of keys being resolved
+struct Resolver<'a> {
+    raw: &'a HashMap<String, String>,
+    keys: HashMap<String, String>,
+    resolved: HashMap<String, String>,
+    failed: HashMap<String, Failure>,
+    stack: Vec<String>,
+}
+
+impl Resolver<'_> {
+    /// Resolve the value of a single property, resolving the properties it
+    /// references first. If it can't be resolved its value is kept as written
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the property key as it was written
+    ///
+    /// # Return
+    /// A Result with the resolved value or a Failure if it can't be resolved
+    fn resolve(&mut self, key: &str) -> Result<String, Failure> {
+        if let Some(f) = self.failed.get(key) {
+            return Err(f.clone());
+        }
+
+        if let Some(v) = self.resolved.get(key) {
+            return Ok(v.clone());
+        }
+
+        // A key that is already being resolved references itself
+        if let Some(start) = self.stack.iter().position(|k| k == key) {
+            let mut chain = self.stack[start..].to_vec();
+            chain.push(key.to_string());
+            return Err(Failure::Cycle(chain));
+        }
+
+        let raw = self.raw[key].clone();
+        self.stack.push(key.to_string());
+        let expanded = self.expand(key, &raw);
+        self.stack.pop();
+
+        match expanded {
+            Ok(value) => {
+                self.resolved.insert(key.to_string(), value.clone());
+                Ok(value)
+            },
+            Err(f) => {
+                self.resolved.insert(key.to_string(), raw);
+                self.failed.insert(key.to_string(), f.clone());
+                Err(f)
+            },
+        }
+    }
+
+    /// Replace every reference in a value with what it refers to
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the key of the property holding the value
+    /// * `raw` - A &str with the value as it was read
+    ///
+    /// # Return
+    /// A Result with the expanded value or a Failure if a reference can't be
+    /// resolved
+    fn expand(&mut self, key: &str, raw: &str) -> Result<String, Failure> {
+        let mut value = String::new();
+        let mut rest = raw;
+
+        while let Some(start) = rest.find('$') {
+            value.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            if rest.starts_with("$${") {
+                value.push_str("${");
+                rest = &rest[3..];
+            } else if rest.starts_with("${") {
+                let end = match rest.find('}') {
+                    Some(e) => e,
+                    None => return Err(Failure::Undefined {
+                        key: key.to_string(),
+                        reference: rest.to_string(),
+                    }),
+                };
+
+                value.push_str(&self.lookup(key, &rest[2..end])?);
+                rest = &rest[end + 1..];
+            } else {
+                value.push('$');
+                rest = &rest[1..];
+            }
+        }
+
+        value.push_str(rest);
+        Ok(value)
+    }
+
+    /// Get the value a single reference refers to
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the key of the property holding the reference
+    /// * `reference` - A &str with the text between `${` and `}`
+    ///
+    /// # Return
+    /// A Result with the referenced value or a Failure if it doesn't exist
+    fn lookup(&mut self, key: &str, reference: &str)
+        -> Result<String, Failure> {
+
+        let unresolved = || Failure::Undefined {
+            key: key.to_string(),
+            reference: format!("${{{}}}", reference),
+        };
+
+        match reference.strip_prefix(ENV_PREFIX) {
+            Some(name) => env::var(name).map_err(|_| unresolved()),
+            None => {
+                // References ignore the case of the key
+                let target = reference.trim().to_uppercase();
+                match self.keys.get(&target).cloned() {
+                    Some(k) => self.resolve(&k),
+                    None => Err(unresolved()),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! This file contains the options for splitting a property value into a list
+//! and the splitting itself
+
+/// How a property value is split into the elements of a list. By default
+/// elements are separated by commas, trimmed, may be quoted to contain the
+/// separator, and empty elements are skipped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListOptions {
+    separator: char,
+    trim: bool,
+    quoted: bool,
+    skip_empty: bool,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListOptions {
+    /// Get the default ListOptions
+    ///
+    /// # Return
+    /// A ListOptions splitting on commas
+    pub fn new() -> Self {
+        Self {
+            separator: ',',
+            trim: true,
+            quoted: true,
+            skip_empty: true,
+        }
+    }
+
+    /// Set the character between elements
+    ///
+    /// # Arguments
+    /// * `separator` - A char separating the elements
+    ///
+    /// # Return
+    /// The ListOptions with the separator set
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Set whether whitespace around each element is removed
+    ///
+    /// # Arguments
+    /// * `trim` - A bool that is true to trim the elements
+    ///
+    /// # Return
+    /// The ListOptions with trimming set
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Set whether elements can be wrapped in single or double quotes, which
+    /// keep the separator and surrounding whitespace inside them
+    ///
+    /// # Arguments
+    /// * `quoted` - A bool that is true to allow quoted elements
+    ///
+    /// # Return
+    /// The ListOptions with quoting set
+    pub fn quoted(mut self, quoted: bool) -> Self {
+        self.quoted = quoted;
+        self
+    }
+
+    /// Set whether empty elements, like the one after a trailing separator,
+    /// are left out of the list
+    ///
+    /// # Arguments
+    /// * `skip_empty` - A bool that is true to skip empty elements
+    ///
+    /// # Return
+    /// The ListOptions with skipping set
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
+    /// Split a property value into its elements
+    ///
+    /// # Arguments
+    /// * `val` - A &str with the property value
+    ///
+    /// # Return
+    /// A Result with the elements or a String saying why the value can't be
+    /// split
+    pub(crate) fn split(&self, val: &str) -> Result<Vec<String>, String> {
+        let mut elements = Vec::new();
+        let mut current = String::new();
+        let mut was_quoted = false;
+        let mut chars = val.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' | '\'' if self.quoted && current.trim().is_empty() => {
+                    // Whitespace before the opening quote isn't kept
+                    current.clear();
+                    loop {
+                        match chars.next() {
+                            Some(q) if q == c => break,
+                            Some(q) => current.push(q),
+                            None => return Err(format!(
+                                "element {} has an unterminated quote",
+                                elements.len())),
+                        };
+                    }
+                    was_quoted = true;
+
+                    // Only whitespace can follow the closing quote
+                    for rest in chars.by_ref() {
+                        if rest == self.separator {
+                            self.push(&mut elements, current, true);
+                            current = String::new();
+                            was_quoted = false;
+                            break;
+                        }
+                        if !rest.is_whitespace() {
+                            return Err(format!("element {} has text after its \
+                                closing quote", elements.len()));
+                        }
+                    }
+                },
+                c if c == self.separator => {
+                    self.push(&mut elements, current, was_quoted);
+                    current = String::new();
+                    was_quoted = false;
+                },
+                c => current.push(c),
+            };
+        }
+
+        self.push(&mut elements, current, was_quoted);
+        Ok(elements)
+    }
+
+    /// Add an element to a list, trimming or skipping it as configured
+    ///
+    /// # Arguments
+    /// * `elements` - The list to add to
+    /// * `element` - A String with the element
+    /// * `was_quoted` - A bool that is true if the element was quoted, so it
+    ///   is kept as written
+    fn push(&self, elements: &mut Vec<String>, element: String,
+        was_quoted: bool) {
+
+        let element = match self.trim && !was_quoted {
+            true => element.trim().to_string(),
+            _ => element,
+        };
+
+        if !(self.skip_empty && !was_quoted && element.is_empty()) {
+            elements.push(element);
+        }
+    }
+}
@@ -1,3 +1,17 @@
 //! This module handles CAPO configuration functionality
+#[allow(clippy::module_inception)]
 pub mod config;
+pub mod builder;
+pub mod diff;
+pub mod document;
+pub mod list;
+pub mod section;
+pub mod settings;
+pub mod source;
 mod config_file;
+mod interpolate;
+
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "watch")]
+pub mod watch;
@@ -0,0 +1,208 @@
+//! This file contains a borrowed view of the CAPO properties below a dotted
+//! key prefix.
+//!
+//! With the section `section2.programA`, the key `run` means
+//! `section2.programA.run`. A component can be handed only its own section
+//! instead of the whole configuration.
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use url::Url;
+
+use super::{
+    config::{
+        CapoConfig,
+        CapoHistoryEntry,
+    },
+    list::ListOptions,
+};
+use crate::helpers::{
+    errors::CapoValueError,
+    keys::strip_prefix_ignoring_case,
+};
+
+/// The CAPO properties below a dotted key prefix
+#[derive(Clone)]
+pub struct CapoSection<'a> {
+    config: &'a CapoConfig,
+    prefix: String,
+}
+
+/// Define getters that take a key relative to the section and return what
+/// the CapoConfig getter of the same name returns for the full key
+macro_rules! section_getters {
+    ($($name:ident -> $ret:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("See `CapoConfig::", stringify!($name), "`, with ",
+                "the key relative to the section")]
+            pub fn $name<S: ToString>(&self, key: &S) -> $ret {
+                self.config.$name(&self.key(key))
+            }
+        )*
+    };
+}
+
+impl CapoConfig {
+    /// Get a view of the properties below a dotted key prefix
+    ///
+    /// # Arguments
+    /// * `prefix` - A ToString with the prefix, like "section2.programA"
+    ///
+    /// # Return
+    /// A CapoSection whose getters take keys relative to the prefix
+    pub fn section<S: ToString>(&self, prefix: &S) -> CapoSection<'_> {
+        CapoSection {
+            config: self,
+            prefix: prefix.to_string().trim_end_matches('.').to_string(),
+        }
+    }
+}
+
+impl<'a> CapoSection<'a> {
+    /// Get the prefix of the section
+    ///
+    /// # Return
+    /// A &str with the prefix, without a trailing dot
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Get the configuration the section is a view of
+    ///
+    /// # Return
+    /// A reference to the CapoConfig
+    pub fn config(&self) -> &'a CapoConfig {
+        self.config
+    }
+
+    /// Get the full key of a key relative to the section
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the relative key
+    ///
+    /// # Return
+    /// A String with the prefix and the key joined by a dot
+    pub fn key<S: ToString>(&self, key: &S) -> String {
+        match self.prefix.is_empty() {
+            true => key.to_string(),
+            _ => format!("{}.{}", self.prefix, key.to_string()),
+        }
+    }
+
+    /// Get the names of the properties and sections directly below this
+    /// section, spelled as they were in the properties files
+    ///
+    /// # Return
+    /// A `Vec<String>` of the names sorted ignoring case, so `section2` has
+    /// the children `programA` and `programB`
+    pub fn children(&self) -> Vec<String> {
+        let start = match self.prefix.is_empty() {
+            true => String::new(),
+            _ => format!("{}.", self.prefix),
+        };
+
+        let mut children: BTreeMap<String, String> = BTreeMap::new();
+        for key in self.config.options.keys() {
+            let rest = match strip_prefix_ignoring_case(key, &start) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            let child = rest.split('.').next().unwrap_or_default();
+            if !child.is_empty() {
+                children.insert(child.to_uppercase(), child.to_string());
+            }
+        }
+
+        children.into_values().collect()
+    }
+
+    /// Get a view of a section below this one
+    ///
+    /// # Arguments
+    /// * `prefix` - A ToString with the prefix relative to this section
+    ///
+    /// # Return
+    /// A CapoSection of the same configuration
+    pub fn section<S: ToString>(&self, prefix: &S) -> CapoSection<'a> {
+        self.config.section(&self.key(prefix))
+    }
+
+    section_getters!(
+        get -> Option<String>,
+        get_raw -> Option<String>,
+        get_location -> Option<String>,
+        get_source -> Option<String>,
+        get_history -> Option<Vec<CapoHistoryEntry>>,
+        get_duration -> Result<Option<Duration>, CapoValueError>,
+        get_bytesize -> Result<Option<u64>, CapoValueError>,
+        get_path -> Result<Option<PathBuf>, CapoValueError>,
+        get_url -> Result<Option<Url>, CapoValueError>,
+        get_u128 -> Option<u128>,
+        get_i128 -> Option<i128>,
+        get_u64 -> Option<u64>,
+        get_i64 -> Option<i64>,
+        get_u32 -> Option<u32>,
+        get_i32 -> Option<i32>,
+        get_u16 -> Option<u16>,
+        get_i16 -> Option<i16>,
+        get_u8 -> Option<u8>,
+        get_i8 -> Option<i8>,
+        get_f64 -> Option<f64>,
+        get_f32 -> Option<f32>,
+        get_usize -> Option<usize>,
+        get_isize -> Option<isize>,
+        get_bool -> Option<bool>,
+    );
+
+    /// See `CapoConfig::get_as`, with the key relative to the section
+    pub fn get_as<T, S>(&self, key: &S) -> Result<Option<T>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.config.get_as(&self.key(key))
+    }
+
+    /// See `CapoConfig::get_or`, with the key relative to the section
+    pub fn get_or<T, S>(&self, key: &S, default: T) -> Result<T, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.config.get_or(&self.key(key), default)
+    }
+
+    /// See `CapoConfig::require`, with the key relative to the section
+    pub fn require<T, S>(&self, key: &S) -> Result<T, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.config.require(&self.key(key))
+    }
+
+    /// See `CapoConfig::get_list`, with the key relative to the section
+    pub fn get_list<T, S>(&self, key: &S)
+        -> Result<Option<Vec<T>>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.config.get_list(&self.key(key))
+    }
+
+    /// See `CapoConfig::get_list_with`, with the key relative to the section
+    pub fn get_list_with<T, S>(&self, key: &S, options: &ListOptions)
+        -> Result<Option<Vec<T>>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.config.get_list_with(&self.key(key), options)
+    }
+
+    /// See `CapoConfig::get_indexed_list`, with the prefix relative to the
+    /// section
+    pub fn get_indexed_list<T, S>(&self, prefix: &S)
+        -> Result<Option<Vec<T>>, CapoValueError>
+        where T: FromStr, T::Err: fmt::Display, S: ToString {
+
+        self.config.get_indexed_list(&self.key(prefix))
+    }
+}
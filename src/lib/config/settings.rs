@@ -0,0 +1,213 @@
+//! This file contains the traits and errors for loading strongly typed
+//! settings structs from a CAPO configuration, usually through
+//! `#[derive(CapoSettings)]`
+use std::{
+    error::Error,
+    fmt,
+    path::PathBuf,
+    time::Duration,
+};
+
+use url::Url;
+
+use super::config::{
+    parse_bool,
+    parse_value,
+    CapoConfig,
+};
+use crate::helpers::{
+    errors::CapoValueError,
+    values::{
+        self,
+        ByteSize,
+    },
+};
+
+/// Types whose fields can all be loaded from CAPO properties
+pub trait CapoSettings: Sized {
+    /// Load every field from a configuration
+    ///
+    /// # Arguments
+    /// * `config` - The CapoConfig to read the properties from
+    ///
+    /// # Return
+    /// A Result with the loaded settings or a CapoSettingsError listing every
+    /// property that was missing or malformed
+    fn from_capo(config: &CapoConfig) -> Result<Self, CapoSettingsError>;
+
+    /// Describe the property behind every field
+    ///
+    /// # Return
+    /// A `Vec<CapoSettingInfo>` in the order the fields are declared
+    fn describe() -> Vec<CapoSettingInfo>;
+}
+
+/// Types that can be parsed from a single CAPO property value, the same way
+/// the typed getters of `CapoConfig` parse them
+pub trait FromCapoValue: Sized {
+    /// Parse a property value
+    ///
+    /// # Arguments
+    /// * `val` - A &str with the property value
+    ///
+    /// # Return
+    /// A Result with the parsed value or a String saying why it can't be
+    /// parsed
+    fn from_capo_value(val: &str) -> Result<Self, String>;
+}
+
+/// Implement FromCapoValue for types parsed like `CapoConfig::get_as`
+macro_rules! from_capo_value_parsed {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl FromCapoValue for $type {
+                fn from_capo_value(val: &str) -> Result<Self, String> {
+                    parse_value::<$type>(val)
+                }
+            }
+        )*
+    };
+}
+
+from_capo_value_parsed!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64,
+    i128, isize, f32, f64, char, String, ByteSize);
+
+impl FromCapoValue for bool {
+    fn from_capo_value(val: &str) -> Result<Self, String> {
+        parse_bool(val)
+            .ok_or_else(|| String::from("expected yes, no, true or false"))
+    }
+}
+
+impl FromCapoValue for Duration {
+    fn from_capo_value(val: &str) -> Result<Self, String> {
+        values::parse_duration(val)
+    }
+}
+
+impl FromCapoValue for PathBuf {
+    fn from_capo_value(val: &str) -> Result<Self, String> {
+        values::expand_path(val)
+    }
+}
+
+impl FromCapoValue for Url {
+    fn from_capo_value(val: &str) -> Result<Self, String> {
+        values::parse_url(val)
+    }
+}
+
+/// The description of the property behind one field of a CapoSettings type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapoSettingInfo {
+    pub key: &'static str,
+    pub type_name: &'static str,
+    pub default: Option<&'static str>,
+    pub required: bool,
+    pub description: Option<&'static str>,
+}
+
+/// A single property that couldn't be loaded into a field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CapoSettingProblem {
+    /// A required property isn't defined and has no default
+    Missing {
+        key: String,
+    },
+
+    /// A property value can't be parsed as the field's type
+    Malformed {
+        key: String,
+        value: String,
+        location: String,
+        type_name: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for CapoSettingProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { key } => write!(f, "missing setting {}", key),
+            Self::Malformed { key, value, location, type_name, reason } =>
+                write!(f, "setting {} from {} has value '{}' which isn't a \
+                valid {}: {}", key, location, value, type_name, reason),
+        }
+    }
+}
+
+/// The error for settings that couldn't be loaded, listing every problem
+/// rather than only the first
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapoSettingsError {
+    pub problems: Vec<CapoSettingProblem>,
+}
+
+impl fmt::Display for CapoSettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problems: Vec<String> = self.problems.iter()
+            .map(|p| p.to_string())
+            .collect();
+
+        write!(f, "{}", problems.join("; "))
+    }
+}
+
+impl Error for CapoSettingsError {}
+
+/// Load a single field for a CapoSettings implementation, noting a problem
+/// instead of stopping when the property is missing or malformed. This is
+/// used by the code `#[derive(CapoSettings)]` generates
+///
+/// # Arguments
+/// * `config` - The CapoConfig to read the property from
+/// * `key` - A &str with the property key value
+/// * `type_name` - A &str with the name of the field's type
+/// * `default` - An `Option<&str>` with the value to parse if the property
+///   isn't defined
+/// * `required` - A bool that is true if the property must be defined
+/// * `problems` - The list to add a problem to
+///
+/// # Return
+/// An Option with the parsed value or None if it's missing or malformed
+#[doc(hidden)]
+pub fn load_setting<T: FromCapoValue>(config: &CapoConfig, key: &str,
+    type_name: &str, default: Option<&str>, required: bool,
+    problems: &mut Vec<CapoSettingProblem>) -> Option<T> {
+
+    let error = match (config.parse_with(&key, type_name, T::from_capo_value),
+        default) {
+
+        (Ok(Some(parsed)), _) => return Some(parsed),
+        (Ok(None), Some(d)) => match T::from_capo_value(d) {
+            Ok(parsed) => return Some(parsed),
+            Err(reason) => CapoValueError::Invalid {
+                key: key.to_string(),
+                value: d.to_string(),
+                location: Some(String::from("default")),
+                type_name: type_name.to_string(),
+                reason,
+            },
+        },
+        (Ok(None), None) => CapoValueError::Missing {
+            key: key.to_string(),
+        },
+        (Err(e), _) => e,
+    };
+
+    match error {
+        CapoValueError::Missing { key } if required =>
+            problems.push(CapoSettingProblem::Missing { key }),
+        CapoValueError::Missing { .. } => {},
+        CapoValueError::Invalid { key, value, location, type_name, reason } =>
+            problems.push(CapoSettingProblem::Malformed {
+                key,
+                value,
+                location: location.unwrap_or_default(),
+                type_name,
+                reason,
+            }),
+    };
+
+    None
+}
@@ -0,0 +1,47 @@
+//! This file contains the INI format, where `[section]` headers become the
+//! first part of the dotted keys below them
+use std::{
+    collections::HashMap,
+    io::BufRead,
+    path::Path,
+};
+
+use ::ini::Ini;
+
+use super::{
+    join_key,
+    read_to_string,
+    ConfigSource,
+};
+use crate::helpers::errors::CapoError;
+
+/// Reads `$profile.ini` files
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IniSource;
+
+impl ConfigSource for IniSource {
+    fn extensions(&self) -> &[&str] {
+        &["ini"]
+    }
+
+    fn read(&self, reader: &mut dyn BufRead, file: &Path)
+        -> Result<HashMap<String, String>, CapoError> {
+
+        let contents = read_to_string(reader, file)?;
+        let ini = Ini::load_from_str(&contents)
+            .map_err(|e| CapoError::Format {
+                file: file.to_path_buf(),
+                reason: e.to_string(),
+            })?;
+
+        let mut options = HashMap::new();
+        for (section, properties) in &ini {
+            for (key, value) in properties.iter() {
+                options.insert(join_key(section.unwrap_or_default(), key),
+                    value.to_string());
+            }
+        }
+
+        Ok(options)
+    }
+}
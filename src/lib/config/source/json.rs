@@ -0,0 +1,64 @@
+//! This file contains the JSON format, where nested objects are flattened into
+//! dotted keys and arrays into numbered keys
+use std::{
+    collections::HashMap,
+    io::BufRead,
+    path::Path,
+};
+
+use serde_json::Value;
+
+use super::{
+    join_key,
+    ConfigSource,
+};
+use crate::helpers::errors::CapoError;
+
+/// Reads `$profile.json` files
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonSource;
+
+impl ConfigSource for JsonSource {
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+
+    fn read(&self, reader: &mut dyn BufRead, file: &Path)
+        -> Result<HashMap<String, String>, CapoError> {
+
+        let value: Value = serde_json::from_reader(reader)
+            .map_err(|e| CapoError::Format {
+                file: file.to_path_buf(),
+                reason: e.to_string(),
+            })?;
+
+        let mut options = HashMap::new();
+        flatten("", &value, &mut options);
+
+        Ok(options)
+    }
+}
+
+/// Add a JSON value and everything below it to the properties
+///
+/// # Arguments
+/// * `key` - A &str with the dotted key of the value
+/// * `value` - The Value to add
+/// * `options` - The properties to add to
+fn flatten(key: &str, value: &Value, options: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(object) => {
+            for (k, v) in object {
+                flatten(&join_key(key, k), v, options);
+            }
+        },
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(&join_key(key, &i.to_string()), v, options);
+            }
+        },
+        Value::Null => {},
+        Value::String(s) => { options.insert(key.to_string(), s.clone()); },
+        v => { options.insert(key.to_string(), v.to_string()); },
+    };
+}
@@ -0,0 +1,124 @@
+//! This module contains the formats CAPO files can be written in.
+//!
+//! Every format flattens its files into dotted keys, so a TOML table `[db]`
+//! with `user = "casa"` holds the same property as `db.user = casa` in a
+//! properties file. Files of every enabled format are found on the CAPO path
+//! as `$profile.<extension>` and layered together.
+use std::{
+    collections::HashMap,
+    fmt,
+    io::BufRead,
+    path::Path,
+    sync::Arc,
+};
+
+use crate::helpers::errors::CapoError;
+
+#[cfg(feature = "ini")]
+pub mod ini;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "toml")]
+pub mod toml;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+/// A format CAPO properties can be read from
+pub trait ConfigSource: fmt::Debug + Send + Sync {
+    /// Get the file extensions of the format, without the leading dot
+    ///
+    /// # Return
+    /// A slice of the extensions, the first being the one profile files are
+    /// looked for with
+    fn extensions(&self) -> &[&str];
+
+    /// Read the properties of a file
+    ///
+    /// # Arguments
+    /// * `reader` - The contents of the file
+    /// * `file` - A &Path with the location of the file, for errors
+    ///
+    /// # Return
+    /// A Result with a HashMap of the flattened dotted keys and their values
+    /// or a CapoError if the file couldn't be read
+    fn read(&self, reader: &mut dyn BufRead, file: &Path)
+        -> Result<HashMap<String, String>, CapoError>;
+}
+
+/// The Java `.properties` format CAPO has always used
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PropertiesSource;
+
+impl ConfigSource for PropertiesSource {
+    fn extensions(&self) -> &[&str] {
+        &["properties"]
+    }
+
+    fn read(&self, reader: &mut dyn BufRead, file: &Path)
+        -> Result<HashMap<String, String>, CapoError> {
+
+        java_properties::read(reader).map_err(|source| CapoError::Parse {
+            file: file.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Join a key below a dotted prefix
+///
+/// # Arguments
+/// * `prefix` - A &str with the prefix, empty at the top of a file
+/// * `key` - A &str with the key below the prefix
+///
+/// # Return
+/// A String with the full dotted key
+#[cfg(any(feature = "ini", feature = "json", feature = "toml",
+    feature = "yaml"))]
+pub(crate) fn join_key(prefix: &str, key: &str) -> String {
+    match prefix.is_empty() {
+        true => key.to_string(),
+        _ => format!("{}.{}", prefix, key),
+    }
+}
+
+/// Read a whole file into a String for formats that can't parse a stream
+///
+/// # Arguments
+/// * `reader` - The contents of the file
+/// * `file` - A &Path with the location of the file, for errors
+///
+/// # Return
+/// A Result with the contents or a CapoError if they couldn't be read
+#[cfg(any(feature = "ini", feature = "toml"))]
+pub(crate) fn read_to_string(reader: &mut dyn BufRead, file: &Path)
+    -> Result<String, CapoError> {
+
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents).map_err(|source| CapoError::Io {
+        file: file.to_path_buf(),
+        source,
+    })?;
+
+    Ok(contents)
+}
+
+/// Get the formats enabled by cargo features, starting with `.properties`
+///
+/// # Return
+/// A `Vec<Arc<dyn ConfigSource>>` in the order a profile's files are layered
+pub(crate) fn default_sources() -> Vec<Arc<dyn ConfigSource>> {
+    #[allow(unused_mut)]
+    let mut sources: Vec<Arc<dyn ConfigSource>> =
+        vec![Arc::new(PropertiesSource)];
+
+    #[cfg(feature = "toml")]
+    sources.push(Arc::new(toml::TomlSource));
+    #[cfg(feature = "yaml")]
+    sources.push(Arc::new(yaml::YamlSource));
+    #[cfg(feature = "json")]
+    sources.push(Arc::new(json::JsonSource));
+    #[cfg(feature = "ini")]
+    sources.push(Arc::new(ini::IniSource));
+
+    sources
+}
@@ -0,0 +1,67 @@
+//! This file contains the TOML format, where nested tables are flattened into
+//! dotted keys and arrays into numbered keys
+use std::{
+    collections::HashMap,
+    io::BufRead,
+    path::Path,
+};
+
+use ::toml::Value;
+
+use super::{
+    join_key,
+    read_to_string,
+    ConfigSource,
+};
+use crate::helpers::errors::CapoError;
+
+/// Reads `$profile.toml` files
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TomlSource;
+
+impl ConfigSource for TomlSource {
+    fn extensions(&self) -> &[&str] {
+        &["toml"]
+    }
+
+    fn read(&self, reader: &mut dyn BufRead, file: &Path)
+        -> Result<HashMap<String, String>, CapoError> {
+
+        let contents = read_to_string(reader, file)?;
+        let table: ::toml::Table = contents.parse()
+            .map_err(|e: ::toml::de::Error| CapoError::Format {
+                file: file.to_path_buf(),
+                reason: e.message().to_string(),
+            })?;
+
+        let mut options = HashMap::new();
+        for (key, value) in &table {
+            flatten(key, value, &mut options);
+        }
+
+        Ok(options)
+    }
+}
+
+/// Add a TOML value and everything below it to the properties
+///
+/// # Arguments
+/// * `key` - A &str with the dotted key of the value
+/// * `value` - The Value to add
+/// * `options` - The properties to add to
+fn flatten(key: &str, value: &Value, options: &mut HashMap<String, String>) {
+    match value {
+        Value::Table(table) => {
+            for (k, v) in table {
+                flatten(&join_key(key, k), v, options);
+            }
+        },
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(&join_key(key, &i.to_string()), v, options);
+            }
+        },
+        Value::String(s) => { options.insert(key.to_string(), s.clone()); },
+        v => { options.insert(key.to_string(), v.to_string()); },
+    };
+}
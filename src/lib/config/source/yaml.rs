@@ -0,0 +1,89 @@
+//! This file contains the YAML format, where nested mappings are flattened
+//! into dotted keys and sequences into numbered keys
+use std::{
+    collections::HashMap,
+    io::BufRead,
+    path::Path,
+};
+
+use serde_yaml::Value;
+
+use super::{
+    join_key,
+    ConfigSource,
+};
+use crate::helpers::errors::CapoError;
+
+/// Reads `$profile.yaml` and `$profile.yml` files
+#[derive(Clone, Copy, Debug, Default)]
+pub struct YamlSource;
+
+impl ConfigSource for YamlSource {
+    fn extensions(&self) -> &[&str] {
+        &["yaml", "yml"]
+    }
+
+    fn read(&self, reader: &mut dyn BufRead, file: &Path)
+        -> Result<HashMap<String, String>, CapoError> {
+
+        let value: Value = serde_yaml::from_reader(reader)
+            .map_err(|e| CapoError::Format {
+                file: file.to_path_buf(),
+                reason: e.to_string(),
+            })?;
+
+        let mut options = HashMap::new();
+        flatten("", &value, &mut options);
+
+        Ok(options)
+    }
+}
+
+/// Add a YAML value and everything below it to the properties
+///
+/// # Arguments
+/// * `key` - A &str with the dotted key of the value
+/// * `value` - The Value to add
+/// * `options` - The properties to add to
+fn flatten(key: &str, value: &Value, options: &mut HashMap<String, String>) {
+    let scalar = match value {
+        Value::Mapping(mapping) => {
+            for (k, v) in mapping {
+                let k = match k {
+                    Value::String(s) => s.clone(),
+                    k => scalar_string(k).unwrap_or_default(),
+                };
+                flatten(&join_key(key, &k), v, options);
+            }
+            return;
+        },
+        Value::Sequence(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(&join_key(key, &i.to_string()), v, options);
+            }
+            return;
+        },
+        Value::Tagged(tagged) => return flatten(key, &tagged.value, options),
+        v => scalar_string(v),
+    };
+
+    if let Some(s) = scalar {
+        options.insert(key.to_string(), s);
+    }
+}
+
+/// Write a YAML scalar the way it would appear in a properties file
+///
+/// # Arguments
+/// * `value` - The Value to write
+///
+/// # Return
+/// An Option with the String or None for a null or a collection
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
@@ -0,0 +1,327 @@
+//! This file contains the struct that watches the CAPO path for changes to the
+//! properties files and reloads the configuration when they happen
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        mpsc,
+        Arc,
+        Mutex,
+        MutexGuard,
+        RwLock,
+        RwLockReadGuard,
+        Weak,
+    },
+    thread,
+};
+
+use notify::{
+    Event,
+    EventKind,
+    RecommendedWatcher,
+    RecursiveMode,
+    Watcher,
+};
+
+use super::{
+    config::CapoConfig,
+    diff::{
+        CapoKeyChange,
+        ConfigDiff,
+    },
+};
+use crate::helpers::errors::CapoError;
+
+/// A function called with every change to the configuration
+type ChangeCallback = Arc<dyn Fn(&ConfigDiff) + Send + Sync>;
+
+/// A function called with the change to a single property
+type KeyCallback = Arc<dyn Fn(&CapoKeyChange) + Send + Sync>;
+
+/// The functions registered to be called when the configuration changes
+#[derive(Clone, Default)]
+struct Callbacks {
+    all: Vec<ChangeCallback>,
+    keys: Vec<(String, KeyCallback)>,
+}
+
+/// The struct for keeping a CapoConfig up to date as its properties files
+/// change. The files are watched for as long as the CapoWatcher exists. A file
+/// that is rewritten in place may be read while it is half written, so files
+/// are best replaced by renaming a new file over them
+pub struct CapoWatcher {
+    config: Arc<RwLock<CapoConfig>>,
+    callbacks: Arc<Mutex<Callbacks>>,
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+impl CapoWatcher {
+    /// Start watching every directory on a configuration's path. A directory
+    /// that doesn't exist yet is watched once it's created
+    ///
+    /// # Arguments
+    /// * `config` - The CapoConfig to keep up to date
+    ///
+    /// # Return
+    /// A Result with the CapoWatcher or a CapoError if a directory couldn't be
+    /// watched
+    pub fn new(config: CapoConfig) -> Result<Self, CapoError> {
+        let config = Arc::new(RwLock::new(config));
+        let callbacks = Arc::new(Mutex::new(Callbacks::default()));
+
+        // The events are handled on a thread of our own, since the watcher
+        // can't be given new directories from inside its event handler
+        let (sender, events) = mpsc::channel();
+        let watcher = notify::recommended_watcher(sender)
+            .map_err(|e| CapoError::Watch(e.to_string()))?;
+        let watcher = Arc::new(Mutex::new(watcher));
+
+        let mut watched: HashSet<PathBuf> = HashSet::new();
+        watch_dirs(&watcher, &mut watched, &config)?;
+
+        let (watched_config, watched_callbacks, weak_watcher) =
+            (config.clone(), callbacks.clone(), Arc::downgrade(&watcher));
+        thread::spawn(move || handle_events(events, weak_watcher, watched,
+            watched_config, watched_callbacks));
+
+        Ok(Self {
+            config,
+            callbacks,
+            _watcher: watcher,
+        })
+    }
+
+    /// Register a function to call with every change to the configuration
+    ///
+    /// # Arguments
+    /// * `callback` - A function taking the ConfigDiff of the change
+    pub fn on_change<F>(&self, callback: F)
+        where F: Fn(&ConfigDiff) + Send + Sync + 'static {
+
+        lock(&self.callbacks).all.push(Arc::new(callback));
+    }
+
+    /// Register a function to call when a single property is added, removed
+    /// or changed
+    ///
+    /// # Arguments
+    /// * `key` - A ToString with the property key value
+    /// * `callback` - A function taking the CapoKeyChange of the property
+    pub fn on_key_change<S, F>(&self, key: &S, callback: F)
+        where S: ToString, F: Fn(&CapoKeyChange) + Send + Sync + 'static {
+
+        lock(&self.callbacks).keys.push((key.to_string(), Arc::new(callback)));
+    }
+
+    /// Get the current configuration. It can't be updated while it's borrowed
+    ///
+    /// # Return
+    /// A RwLockReadGuard of the CapoConfig
+    pub fn config(&self) -> RwLockReadGuard<'_, CapoConfig> {
+        read(&self.config)
+    }
+
+    /// Get a handle to the configuration that can be shared with other
+    /// threads
+    ///
+    /// # Return
+    /// An `Arc<RwLock<CapoConfig>>` that is updated as the files change
+    pub fn shared_config(&self) -> Arc<RwLock<CapoConfig>> {
+        self.config.clone()
+    }
+}
+
+/// Handle the watcher's events until it's dropped, refreshing the
+/// configuration when one of its files changes and watching the directories
+/// on its path as they're created
+///
+/// # Arguments
+/// * `events` - The Receiver the watcher sends its events to
+/// * `watcher` - A Weak reference to the watcher, to watch new directories
+/// * `watched` - The directories already being watched
+/// * `config` - The shared CapoConfig to refresh
+/// * `callbacks` - The shared Callbacks to call
+fn handle_events(events: mpsc::Receiver<notify::Result<Event>>,
+    watcher: Weak<Mutex<RecommendedWatcher>>, mut watched: HashSet<PathBuf>,
+    config: Arc<RwLock<CapoConfig>>, callbacks: Arc<Mutex<Callbacks>>) {
+
+    // The events stop once the watcher is dropped
+    for result in events {
+        let event = match result {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("WARNING: {}", e);
+                continue;
+            },
+        };
+
+        let watcher = match (&event.kind, watcher.upgrade()) {
+            (EventKind::Access(_), _) => continue,
+            (_, Some(w)) => w,
+            (_, None) => break,
+        };
+
+        // A removed directory has to be watched again if it's created again
+        if let EventKind::Remove(_) = event.kind {
+            for path in &event.paths {
+                watched.remove(path);
+            }
+        }
+
+        // A directory on the path that was just created may already hold
+        // files, so it needs a refresh as well
+        let created = watch_dirs(&watcher, &mut watched, &config)
+            .unwrap_or_else(|e| {
+                eprintln!("WARNING: {}", e);
+                false
+            });
+
+        // Only events for the files the profiles use need a refresh, and the
+        // profiles may have started extending other files
+        let filenames: HashSet<OsString> = read(&config)
+            .tracked_files()
+            .iter()
+            .filter_map(|file| file.file_name())
+            .map(|name| name.to_owned())
+            .collect();
+
+        let relevant = created || event.paths.iter()
+            .filter_map(|p| p.file_name())
+            .any(|name| filenames.contains(name));
+
+        if relevant {
+            refresh(&config, &callbacks);
+            if let Err(e) = watch_dirs(&watcher, &mut watched, &config) {
+                eprintln!("WARNING: {}", e);
+            }
+        }
+    }
+}
+
+/// Watch the directories of every file the configuration depends on. A
+/// directory that doesn't exist can't be watched, so the nearest one above it
+/// that does is watched instead, to see it being created
+///
+/// # Arguments
+/// * `watcher` - The watcher to give the directories to
+/// * `watched` - The directories already being watched
+/// * `config` - The shared CapoConfig whose files are watched
+///
+/// # Return
+/// A Result with true if a directory of the configuration's files was
+/// watched for the first time, or a CapoError if one couldn't be watched
+fn watch_dirs(watcher: &Mutex<RecommendedWatcher>,
+    watched: &mut HashSet<PathBuf>, config: &RwLock<CapoConfig>)
+    -> Result<bool, CapoError> {
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let tracked = read(config).tracked_files();
+    for dir in tracked.iter().filter_map(|file| file.parent()) {
+        let dir = match dir.as_os_str().is_empty() {
+            true => Path::new("."),
+            _ => dir,
+        };
+
+        if !dirs.iter().any(|d| d == dir) {
+            dirs.push(dir.to_path_buf());
+        }
+    }
+
+    let mut found = false;
+    for dir in dirs {
+        // A directory created before the one above it was watched raised no
+        // event, so look again until the nearest one is watched
+        loop {
+            let existing = match dir.ancestors().find(|d| d.is_dir()) {
+                Some(d) => d.to_path_buf(),
+                None if dir.is_relative() => PathBuf::from("."),
+                None => break,
+            };
+
+            if watched.contains(&existing) {
+                break;
+            }
+
+            watcher.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .watch(&existing, RecursiveMode::NonRecursive)
+                .map_err(|e| CapoError::Watch(
+                    format!("{}: {}", existing.display(), e)))?;
+
+            found |= existing == dir;
+            watched.insert(existing);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Refresh the configuration and call the registered functions with the
+/// properties that changed
+///
+/// # Arguments
+/// * `config` - The shared CapoConfig to refresh
+/// * `callbacks` - The shared Callbacks to call
+fn refresh(config: &RwLock<CapoConfig>, callbacks: &Mutex<Callbacks>) {
+    let mut config = config.write().unwrap_or_else(|e| e.into_inner());
+    let (old_options, old_locations) =
+        (config.options.clone(), config.locations.clone());
+
+    match config.refresh_if_changed() {
+        Ok(true) => {},
+        Ok(false) => return,
+        Err(e) => {
+            eprintln!("WARNING: {}", e);
+            return;
+        },
+    };
+
+    let diff = ConfigDiff::from_maps(&old_options, &old_locations,
+        &config.options, &config.locations);
+
+    // Callbacks may want to read the new configuration
+    drop(config);
+    if diff.is_empty() {
+        return;
+    }
+
+    // Callbacks may register others, so they're called without the lock held
+    let callbacks = lock(callbacks).clone();
+    for callback in &callbacks.all {
+        callback(&diff);
+    }
+
+    for (key, callback) in &callbacks.keys {
+        if let Some(change) = diff.get(key) {
+            callback(change);
+        }
+    }
+}
+
+/// Lock the configuration for reading, even if a callback panicked while
+/// holding it
+///
+/// # Arguments
+/// * `config` - The shared CapoConfig to lock
+///
+/// # Return
+/// A RwLockReadGuard of the CapoConfig
+fn read(config: &RwLock<CapoConfig>) -> RwLockReadGuard<'_, CapoConfig> {
+    config.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Lock the registered functions, even if a callback panicked while holding
+/// them
+///
+/// # Arguments
+/// * `callbacks` - The shared Callbacks to lock
+///
+/// # Return
+/// A MutexGuard of the Callbacks
+fn lock(callbacks: &Mutex<Callbacks>) -> MutexGuard<'_, Callbacks> {
+    callbacks.lock().unwrap_or_else(|e| e.into_inner())
+}
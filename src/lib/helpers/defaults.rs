@@ -8,3 +8,6 @@ pub const CAPO_ENV_VAR: &str = "CAPO_PROFILE";
 
 /// Default environment variable name containing the CAPO path
 pub const CAPO_PATH_VAR: &str = "CAPO_PATH";
+
+/// Default prefix of the environment variables that override CAPO properties
+pub const CAPO_OVERRIDE_PREFIX: &str = "CAPO__";
@@ -1,59 +1,263 @@
 //! Provides error definitions for the application
-use const_format::{
-    concatcp,
-    formatcp,
+use std::{
+    error::Error,
+    fmt,
+    io,
+    path::PathBuf,
 };
-use super::defaults;
 
-/// Encapsulates an error message with its associated status code
-pub struct CapoError<'a> {
-    status_code: i32,
-    error_msg: &'a str,
+/// The ways RustCAPO can fail, each with its associated status code
+#[derive(Debug)]
+pub enum CapoError {
+    /// The profile can't be determined, with the environment variable that
+    /// was checked for it if there was one
+    ProfileMissing {
+        env_var: Option<String>,
+    },
+
+    /// Neither -A, --explain, a selector nor a list of settings was given to
+    /// the command line utility
+    OptionMissing,
+
+    /// A requested setting isn't defined in any properties file
+    SettingMissing(String),
+
+    /// No properties files for the profile could be found on the path
+    NoFilesFound {
+        profile: String,
+        path: Vec<PathBuf>,
+    },
+
+    /// The user's home directory can't be found
+    HomeDirMissing,
+
+    /// A properties file was opened but its properties couldn't be read
+    Parse {
+        file: PathBuf,
+        source: java_properties::PropertiesError,
+    },
+
+    /// A properties file couldn't be opened
+    Io {
+        file: PathBuf,
+        source: io::Error,
+    },
+
+    /// Property values reference each other in a loop, starting and ending
+    /// with the same key
+    InterpolationCycle {
+        chain: Vec<String>,
+    },
+
+    /// A property value references a property or environment variable that
+    /// isn't defined
+    UnresolvedReference {
+        key: String,
+        reference: String,
+    },
+
+    /// The properties files couldn't be watched for changes
+    Watch(String),
+
+    /// Two keys differ only in case, with the file of the second one
+    CaseConflict {
+        first: String,
+        second: String,
+        file: PathBuf,
+    },
+
+    /// Profiles extend each other in a loop, starting and ending with the
+    /// same profile
+    ProfileCycle {
+        chain: Vec<String>,
+    },
+
+    /// Properties files include each other in a loop, starting and ending
+    /// with the same file
+    IncludeCycle {
+        chain: Vec<PathBuf>,
+    },
+
+    /// A properties file includes a pattern that can't be expanded
+    Include {
+        file: PathBuf,
+        include: String,
+        reason: String,
+    },
+
+    /// A file in a format other than `.properties` was opened but its
+    /// properties couldn't be read
+    Format {
+        file: PathBuf,
+        reason: String,
+    },
+
+    /// None of the properties files that were read can be written to
+    NoWritableFile {
+        profile: String,
+    },
+
+    /// Two settings would be printed as the same shell variable
+    NameCollision {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    /// A glob or regular expression given to select settings isn't valid
+    InvalidPattern {
+        pattern: String,
+        reason: String,
+    },
+
+    /// A diff wasn't given exactly two profiles or two paths to compare
+    DiffTargets,
+
+    /// A setting printed as NUL separated records has a NUL byte in it
+    NulByte(String),
 }
 
-impl CapoError<'_> {
-    /// CapoError method that prints the associated CapoError's message to
-    /// stderr and exits the process with its status code
-    pub fn exit_on_error(&self) {
-        eprintln!("{}", self.error_msg);
-        std::process::exit(self.status_code);
+impl CapoError {
+    /// Get the status code a process should exit with for this error
+    ///
+    /// # Return
+    /// An i32 with the status code
+    pub fn status_code(&self) -> i32 {
+        match self {
+            Self::ProfileMissing { .. } => 2,
+            Self::OptionMissing => 3,
+            Self::SettingMissing(_) => 4,
+            Self::NoFilesFound { .. } => 5,
+            Self::HomeDirMissing => 6,
+            Self::Parse { .. } => 7,
+            Self::Io { .. } => 8,
+            Self::InterpolationCycle { .. } => 9,
+            Self::UnresolvedReference { .. } => 10,
+            Self::Watch(_) => 11,
+            Self::CaseConflict { .. } => 12,
+            Self::ProfileCycle { .. } => 13,
+            Self::IncludeCycle { .. } => 14,
+            Self::Include { .. } => 15,
+            Self::Format { .. } => 16,
+            Self::NoWritableFile { .. } => 17,
+            Self::NameCollision { .. } => 18,
+            Self::InvalidPattern { .. } => 19,
+            Self::DiffTargets => 20,
+            Self::NulByte(_) => 21,
+        }
     }
 }
 
-// Go-to error messages for common ways the program can fail
-
-/// Default error for when the profile can't be determined
-pub const PROFILE_ERROR: CapoError = CapoError {
-    status_code: 2,
-    error_msg: concatcp!("ERROR: RustCAPO can't deduce the 'profile', give it ",
-            formatcp!{
-                "the -P argument or set the {} environment variable!",
-                defaults::CAPO_ENV_VAR
-            }
-        ), 
-};
+impl fmt::Display for CapoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProfileMissing { env_var: Some(var) } => write!(f,
+                "RustCAPO can't deduce the 'profile', give it the -P argument \
+                or set the {} environment variable!", var),
+            Self::ProfileMissing { env_var: None } => write!(f,
+                "RustCAPO can't deduce the 'profile', give it the -P \
+                argument!"),
+            Self::OptionMissing => write!(f,
+                "either -A, --explain, --prefix, --glob, --regex or a list of \
+                settings is needed!"),
+            Self::SettingMissing(key) => write!(f, "missing setting {}", key),
+            Self::NoFilesFound { profile, path } => write!(f,
+                "unable to locate CAPO files for profile '{}' in the path '{}'",
+                profile, path.iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(":")),
+            Self::HomeDirMissing =>
+                write!(f, "unable to find the user's home directory"),
+            Self::Parse { file, source } => write!(f,
+                "couldn't read properties from file {}: {}", file.display(),
+                source),
+            Self::Io { file, source } => write!(f,
+                "couldn't open file {}: {}", file.display(), source),
+            Self::InterpolationCycle { chain } => write!(f,
+                "settings reference each other in a cycle: {}",
+                chain.join(" -> ")),
+            Self::UnresolvedReference { key, reference } => write!(f,
+                "setting {} references {} which isn't defined", key,
+                reference),
+            Self::Watch(reason) => write!(f,
+                "couldn't watch the CAPO path for changes: {}", reason),
+            Self::CaseConflict { first, second, file } => write!(f,
+                "setting {} in file {} differs only in case from {}", second,
+                file.display(), first),
+            Self::ProfileCycle { chain } => write!(f,
+                "profiles extend each other in a cycle: {}",
+                chain.join(" -> ")),
+            Self::IncludeCycle { chain } => write!(f,
+                "files include each other in a cycle: {}", chain.iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(" -> ")),
+            Self::Include { file, include, reason } => write!(f,
+                "file {} can't include {}: {}", file.display(), include,
+                reason),
+            Self::Format { file, reason } => write!(f,
+                "couldn't read properties from file {}: {}", file.display(),
+                reason),
+            Self::NoWritableFile { profile } => write!(f,
+                "none of the properties files read for profile '{}' can be \
+                written to, choose one with --file", profile),
+            Self::NameCollision { name, first, second } => write!(f,
+                "settings {} and {} would both be printed as {}", first,
+                second, name),
+            Self::InvalidPattern { pattern, reason } => write!(f,
+                "'{}' isn't a valid pattern: {}", pattern, reason),
+            Self::DiffTargets => write!(f,
+                "diff compares two profiles, like -P test -P production, or two \
+                paths, like --path A --path B"),
+            Self::NulByte(key) => write!(f,
+                "setting {} has a NUL byte, which would split its record", key),
+        }
+    }
+}
 
-/// Default error for when an option argument is missing
-pub const OPTION_ERROR: CapoError = CapoError {
-    status_code: 3,
-    error_msg: "ERROR: either -A or a list of settings is needed!",
-};
+impl Error for CapoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Parse { source, .. } => Some(source),
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
-/// Default error for when a setting is missing
-pub const SETTING_ERROR: CapoError = CapoError {
-    status_code: 4,
-    error_msg: "ERROR: missing setting ",
-};
+/// The ways a single property value can fail to be read as a type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CapoValueError {
+    /// A required property isn't defined in any properties file
+    Missing {
+        key: String,
+    },
 
-/// Default error for when property files can't be found
-pub const PATH_ERROR: CapoError = CapoError {
-    status_code: 5,
-    error_msg: concat!("Error: unable to locate CAPO files in the required ",
-        "path using the current Pofile"),
-};
+    /// A property value can't be parsed as the requested type, with the
+    /// location it was read from if it's known
+    Invalid {
+        key: String,
+        value: String,
+        location: Option<String>,
+        type_name: String,
+        reason: String,
+    },
+}
 
-/// Default error for when the user's home directory can't be found
-pub const HOME_DIR_ERROR: CapoError = CapoError {
-    status_code: 6,
-    error_msg: "ERROR: Unable to find the user's home directory"
-};
+impl fmt::Display for CapoValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { key } => write!(f, "missing setting {}", key),
+            Self::Invalid { key, value, location: Some(location), type_name,
+                reason } => write!(f,
+                "setting {} from {} has value '{}' which isn't a valid {}: {}",
+                key, location, value, type_name, reason),
+            Self::Invalid { key, value, location: None, type_name, reason } =>
+                write!(f, "setting {} has value '{}' which isn't a valid {}: \
+                {}", key, value, type_name, reason),
+        }
+    }
+}
+
+impl Error for CapoValueError {}
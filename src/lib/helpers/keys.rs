@@ -0,0 +1,34 @@
+//! Provides helpers for comparing property keys, which ignore case
+
+/// Remove a prefix from a key, ignoring case. The key is walked by char and
+/// compared in upper case, since upper casing can change how many bytes a char
+/// takes
+///
+/// # Arguments
+/// * `key` - A &str with the key as the files spell it
+/// * `prefix` - A &str with the prefix in any case
+///
+/// # Return
+/// An Option with the rest of the key as the files spell it, or None if the
+/// key doesn't start with the prefix
+pub fn strip_prefix_ignoring_case<'k>(key: &'k str, prefix: &str)
+    -> Option<&'k str> {
+
+    let prefix = prefix.to_uppercase();
+    let mut upper = String::new();
+    for (index, c) in key.char_indices() {
+        if upper == prefix {
+            return Some(&key[index..]);
+        }
+
+        upper.extend(c.to_uppercase());
+        if !prefix.starts_with(&upper) {
+            return None;
+        }
+    }
+
+    match upper == prefix {
+        true => Some(""),
+        _ => None,
+    }
+}
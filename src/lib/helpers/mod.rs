@@ -1,3 +1,5 @@
 //! This module contains default values and helper methods for CAPO
 pub mod errors;
 pub mod defaults;
+pub mod keys;
+pub mod values;
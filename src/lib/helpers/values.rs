@@ -0,0 +1,194 @@
+//! Provides parsers for the property values that aren't plain numbers or
+//! strings, like durations and byte sizes
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use url::Url;
+
+/// A size in bytes, parsed from a value like "512MiB" the same way as
+/// `CapoConfig::get_bytesize`, for settings fields and `get_as`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+impl FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_bytesize(s).map(ByteSize)
+    }
+}
+
+/// Parse a duration made of whole numbers followed by units, like "30s", "5m"
+/// or "1h30m". The units are ms, s, m, h, d and w, and a number without a unit
+/// is in seconds
+///
+/// # Arguments
+/// * `val` - A &str with the property value
+///
+/// # Return
+/// A Result with the Duration or a String saying why it can't be parsed
+pub fn parse_duration(val: &str) -> Result<Duration, String> {
+    let val = val.trim();
+    if val.is_empty() {
+        return Err(String::from("the duration is empty"));
+    }
+
+    // A bare number is a count of seconds
+    if let Ok(seconds) = val.parse::<u64>() {
+        return Ok(Duration::from_secs(seconds));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = val;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(format!("expected a number at '{}'", rest));
+        }
+
+        let count: u64 = rest[..digits].parse()
+            .map_err(|_| format!("{} is too large", &rest[..digits]))?;
+        rest = &rest[digits..];
+
+        let unit_len = rest.find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let millis: u64 = match rest[..unit_len].trim() {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60 * 1_000,
+            "h" => 60 * 60 * 1_000,
+            "d" => 24 * 60 * 60 * 1_000,
+            "w" => 7 * 24 * 60 * 60 * 1_000,
+            "" => return Err(format!("{} is missing a unit", count)),
+            u => return Err(format!("unknown unit '{}'", u)),
+        };
+        rest = &rest[unit_len..];
+
+        total = count.checked_mul(millis)
+            .map(Duration::from_millis)
+            .and_then(|d| total.checked_add(d))
+            .ok_or_else(|| String::from("the duration is too large"))?;
+    }
+
+    Ok(total)
+}
+
+/// Parse a size in bytes, like "512MiB" or "2G". Units ending in iB and the
+/// single letters K, M, G and T are powers of 1024, while units ending in B
+/// alone are powers of 1000. Units ignore case and a number without a unit is
+/// in bytes
+///
+/// # Arguments
+/// * `val` - A &str with the property value
+///
+/// # Return
+/// A Result with the number of bytes as a u64 or a String saying why it can't
+/// be parsed
+pub fn parse_bytesize(val: &str) -> Result<u64, String> {
+    let val = val.trim();
+    let split = val.find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(val.len());
+    let (number, unit) = (&val[..split], val[split..].trim());
+
+    // The whole part is kept exact, since a float can't hold every u64
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+        return Err(format!("expected a number at '{}'", val));
+    }
+
+    let multiplier: u64 = match unit.to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        _ => return Err(format!("unknown unit '{}'", unit)),
+    };
+
+    let whole: u64 = match whole {
+        "" => 0,
+        w => w.parse().map_err(|_| String::from("the size is too large"))?,
+    };
+
+    let fraction = fraction_bytes(fraction, multiplier)?;
+    whole.checked_mul(multiplier)
+        .and_then(|b| b.checked_add(fraction))
+        .ok_or_else(|| String::from("the size is too large"))
+}
+
+/// Get the bytes the fractional part of a size stands for
+///
+/// # Arguments
+/// * `digits` - A &str with the digits after the decimal point
+/// * `multiplier` - A u64 with the bytes in one of the size's unit
+///
+/// # Return
+/// A Result with the number of bytes or a String saying why they aren't a
+/// whole number
+fn fraction_bytes(digits: &str, multiplier: u64) -> Result<u64, String> {
+    let digits = digits.trim_end_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+
+    // Scaling more digits by the largest unit could overflow a u128
+    if digits.len() > 26 {
+        return Err(String::from("the size has too many decimal places"));
+    }
+
+    let scaled = digits.parse::<u128>()
+        .map_err(|_| format!("expected a number at '{}'", digits))?
+        * u128::from(multiplier);
+    let denominator = 10u128.pow(digits.len() as u32);
+
+    // The fraction is less than one unit, so the bytes fit in a u64
+    match scaled % denominator {
+        0 => Ok((scaled / denominator) as u64),
+        _ => Err(String::from("the size isn't a whole number of bytes")),
+    }
+}
+
+/// Parse a path, replacing a leading `~` with the user's home directory
+///
+/// # Arguments
+/// * `val` - A &str with the property value
+///
+/// # Return
+/// A Result with the PathBuf or a String saying why it can't be parsed
+pub fn expand_path(val: &str) -> Result<PathBuf, String> {
+    if val.is_empty() {
+        return Err(String::from("the path is empty"));
+    }
+
+    let rest = match val.strip_prefix('~') {
+        Some(r) if r.is_empty() || r.starts_with('/') => r,
+        _ => return Ok(PathBuf::from(val)),
+    };
+
+    let home = dirs::home_dir().ok_or_else(||
+        String::from("unable to find the user's home directory"))?;
+
+    match rest.trim_start_matches('/') {
+        "" => Ok(home),
+        r => Ok(home.join(r)),
+    }
+}
+
+/// Parse a URL
+///
+/// # Arguments
+/// * `val` - A &str with the property value
+///
+/// # Return
+/// A Result with the Url or a String saying why it can't be parsed
+pub fn parse_url(val: &str) -> Result<Url, String> {
+    Url::parse(val).map_err(|e| e.to_string())
+}
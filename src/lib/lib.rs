@@ -1,6 +1,36 @@
+extern crate self as rustcapo;
+
 pub mod config;
 pub mod helpers;
 mod tests;
 
-pub use config::config::CapoConfig;
+pub use config::config::{
+    CapoConfig,
+    CapoFileStatus,
+};
+pub use config::builder::CapoConfigBuilder;
+pub use config::diff::{
+    CapoKeyChange,
+    ConfigDiff,
+};
+pub use config::document::{
+    PropertiesDocument,
+    PropertiesEntry,
+    PropertiesLine,
+};
+pub use config::list::ListOptions;
+pub use config::section::CapoSection;
+pub use config::source::ConfigSource;
+pub use config::settings::CapoSettings;
+#[cfg(feature = "derive")]
+pub use rustcapo_derive::CapoSettings;
+#[cfg(feature = "serde")]
+pub use config::de::CapoDeError;
+#[cfg(feature = "watch")]
+pub use config::watch::CapoWatcher;
 pub use helpers::errors;
+pub use url::Url;
+pub use helpers::errors::{
+    CapoError,
+    CapoValueError,
+};
@@ -0,0 +1,131 @@
+#[allow(unused_imports)]
+use std::path::PathBuf;
+#[allow(unused_imports)]
+use crate::config::builder::CapoConfigBuilder;
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoError;
+#[allow(unused_imports)]
+use super::{
+    builder,
+    load,
+};
+#[allow(unused_imports)]
+use super::common::{
+    scratch_dir,
+    write_file,
+};
+
+#[test]
+fn test_hermetic_build() {
+    let config = CapoConfigBuilder::new()
+        .ignore_environment()
+        .append_user_dir(false)
+        .profile("test")
+        .path(vec![PathBuf::from("src/lib/tests")])
+        .build()
+        .unwrap();
+
+    assert_eq!(config.path, vec![PathBuf::from("src/lib/tests")]);
+    assert_eq!(config.cfg_files.len(), 1);
+    assert_eq!(config.get(&"section1.database.user").unwrap(), "user");
+}
+
+#[test]
+fn test_missing_profile_without_environment() {
+    let result = CapoConfigBuilder::new()
+        .ignore_environment()
+        .path(vec![PathBuf::from("src/lib/tests")])
+        .build();
+
+    match result {
+        Err(CapoError::ProfileMissing { env_var: None }) => {},
+        _ => panic!("expected a ProfileMissing error"),
+    }
+}
+
+#[test]
+fn test_custom_profile_env_var() {
+    let result = CapoConfigBuilder::new()
+        .profile_env_var("RUSTCAPO_TEST_UNSET_PROFILE")
+        .path(vec![PathBuf::from("src/lib/tests")])
+        .build();
+
+    match result {
+        Err(CapoError::ProfileMissing { env_var: Some(var) }) =>
+            assert_eq!(var, "RUSTCAPO_TEST_UNSET_PROFILE"),
+        _ => panic!("expected a ProfileMissing error"),
+    }
+}
+
+#[test]
+fn test_extra_source_overrides_path() {
+    let config = builder("test", &["src/lib/tests"])
+        .source("src/lib/tests/extra/override.properties")
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get(&"section1.database.user").unwrap(), "admin");
+    assert_eq!(config.get_location(&"section1.database.user").unwrap(),
+        "override.properties");
+    assert_eq!(config.get(&"section1.database.password").unwrap(), "password");
+}
+
+#[test]
+fn test_lenient_skips_unreadable_files() {
+    let config = load("test", &["src/lib/tests", "src/lib/tests/broken",
+        "src/lib/tests/missing"]).unwrap();
+
+    assert_eq!(config.cfg_files.len(), 1);
+
+    // Skipped files are listed after the files that were read with the
+    // reason they were skipped
+    let files = config.files();
+    assert_eq!(files.len(), 3);
+    assert_eq!(files[0].file, PathBuf::from("src/lib/tests/test.properties"));
+    assert_eq!(files[0].skipped, None);
+    assert_eq!(files[1].file,
+        PathBuf::from("src/lib/tests/broken/test.properties"));
+    assert!(files[1].skipped.is_some());
+    assert_eq!(files[2].file,
+        PathBuf::from("src/lib/tests/missing/test.properties"));
+    assert_eq!(files[2].profile, Some(String::from("test")));
+    assert!(files[2].skipped.is_some());
+}
+
+#[test]
+fn test_strict_rejects_unreadable_files() {
+    // Profile files missing from the path are still skipped
+    let config = builder("test", &["src/lib/tests", "src/lib/tests/missing"])
+        .strict(true)
+        .build()
+        .unwrap();
+    assert_eq!(config.cfg_files.len(), 1);
+
+    let result = builder("test", &["src/lib/tests", "src/lib/tests/broken"])
+        .strict(true)
+        .build();
+
+    match result {
+        Err(CapoError::Parse { file, .. }) => assert_eq!(file,
+            PathBuf::from("src/lib/tests/broken/test.properties")),
+        _ => panic!("expected a Parse error"),
+    }
+}
+
+#[test]
+fn test_files_without_resolving() {
+    let dir = scratch_dir("builder-files");
+    write_file(&dir.join("base.properties"), "b = 1\n", 1);
+    write_file(&dir.join("test.properties"),
+        "capo.profile.extends = base\na = ${missing}\n", 1);
+    let files_of = builder("test", &[&dir]).strict(true);
+
+    assert!(files_of.clone().build().is_err());
+
+    // The files are still found, with the profiles they extend
+    let files = files_of.files().unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].file, dir.join("base.properties"));
+    assert_eq!(files[1].file, dir.join("test.properties"));
+    assert_eq!(files[1].skipped, None);
+}
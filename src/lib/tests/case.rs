@@ -0,0 +1,45 @@
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoError;
+#[allow(unused_imports)]
+use super::{
+    builder,
+    load,
+};
+
+#[test]
+fn test_keys_keep_their_case() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+
+    assert!(config.options.contains_key("section2.programB.contactList"));
+    assert_eq!(config.get_key(&"SECTION2.PROGRAMB.CONTACTLIST"),
+        Some(String::from("section2.programB.contactList")));
+    assert_eq!(config.get(&"Section2.ProgramB.ContactList"),
+        Some(String::from("apples@banannas.com")));
+    assert_eq!(config.get_location(&"section2.programb.contactlist"),
+        Some(String::from("test.properties")));
+}
+
+#[test]
+fn test_case_conflicts() {
+    let config = load("test", &["src/lib/tests/case"]).unwrap();
+
+    // The conflicting keys are one property, sorted so the lowercase one wins
+    assert_eq!(config.get_key(&"archive.host"),
+        Some(String::from("archive.host")));
+    assert_eq!(config.get(&"ARCHIVE.HOST"),
+        Some(String::from("second.example.org")));
+    assert_eq!(config.get_history(&"archive.host").unwrap().len(), 2);
+    assert!(!config.options.contains_key("archive.Host"));
+
+    // References ignore case too
+    assert_eq!(config.get(&"archive.link"),
+        Some(String::from("/archive/data")));
+
+    match builder("test", &["src/lib/tests/case"]).strict(true).build() {
+        Err(CapoError::CaseConflict { first, second, .. }) => {
+            assert_eq!(first, "archive.Host");
+            assert_eq!(second, "archive.host");
+        },
+        _ => panic!("expected a CaseConflict error"),
+    }
+}
@@ -0,0 +1,49 @@
+//! Helpers shared by tests that need to write their own properties files
+#![allow(dead_code)]
+use std::{
+    env,
+    fs::{
+        self,
+        File,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    process,
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+
+/// Create an empty directory for a test to write properties files in
+///
+/// # Arguments
+/// * `name` - A &str unique to the test using the directory
+///
+/// # Return
+/// A PathBuf with the location of the directory
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("rustcapo-{}-{}", name, process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write a properties file with a modification time some seconds after the
+/// epoch, so rewriting it always changes the modification time
+///
+/// # Arguments
+/// * `file` - A &Path with the location of the file
+/// * `contents` - A &str with the contents of the file
+/// * `seconds` - A u64 with the modification time in seconds after the epoch
+pub fn write_file(file: &Path, contents: &str, seconds: u64) {
+    fs::write(file, contents).unwrap();
+
+    File::options().write(true).open(file).unwrap()
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+        .unwrap();
+}
@@ -0,0 +1,139 @@
+#[allow(unused_imports)]
+use std::collections::BTreeMap;
+#[allow(unused_imports)]
+use serde::Deserialize;
+#[allow(unused_imports)]
+use super::load;
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, PartialEq)]
+struct Program {
+    run: bool,
+    output: Option<String>,
+    contact_list: Option<Vec<String>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, PartialEq)]
+struct Section2 {
+    program_a: Program,
+    program_b: Program,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, PartialEq)]
+enum Mode {
+    Batch,
+    Interactive,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, PartialEq)]
+enum Storage {
+    Disk { path: String, quota: u32 },
+    Tape(String),
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, PartialEq)]
+struct Pipeline {
+    name: String,
+    mode: Mode,
+    retries: u8,
+    hosts: Vec<String>,
+    stages: Vec<String>,
+    storage: Storage,
+    timeout: Option<u32>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+struct Broken {
+    retries: u32,
+}
+
+#[test]
+fn test_deserialize_prefix() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+
+    let program: Program = config.deserialize("section2.programA").unwrap();
+    assert_eq!(program, Program {
+        run: true,
+        output: Some(String::from("/testing/resources")),
+        contact_list: None,
+    });
+}
+
+#[test]
+fn test_deserialize_nested() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+
+    let section: Section2 = config.deserialize("section2").unwrap();
+    assert!(section.program_a.run);
+    assert!(!section.program_b.run);
+    assert_eq!(section.program_b.contact_list,
+        Some(vec![String::from("apples@banannas.com")]));
+}
+
+#[test]
+fn test_deserialize_lists_and_enums() {
+    let config = load("test", &["src/lib/tests/de"]).unwrap();
+
+    let pipeline: Pipeline = config.deserialize("pipeline").unwrap();
+    assert_eq!(pipeline, Pipeline {
+        name: String::from("calibration"),
+        mode: Mode::Batch,
+        retries: 3,
+        hosts: vec![String::from("alpha"), String::from("beta")],
+        stages: vec![String::from("fetch"), String::from("calibrate"),
+            String::from("image"), String::from("archive")],
+        storage: Storage::Disk {
+            path: String::from("/data"),
+            quota: 512,
+        },
+        timeout: None,
+    });
+}
+
+#[test]
+fn test_deserialize_single_value() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+
+    let hundred: u32 = config.deserialize("section3.integer.hundred").unwrap();
+    assert_eq!(hundred, 100);
+}
+
+#[test]
+fn test_deserialize_errors() {
+    let config = load("test", &["src/lib/tests/de"]).unwrap();
+
+    let error = config.deserialize::<Broken>("broken").unwrap_err();
+    assert_eq!(error.key.as_deref(), Some("broken.retries"));
+    assert_eq!(error.location.as_deref(), Some("src/lib/tests/de/test.properties"));
+    assert!(error.to_string().contains("many"));
+
+    let error = config.deserialize::<Program>("pipeline").unwrap_err();
+    assert_eq!(error.key.as_deref(), Some("pipeline"));
+    assert!(error.message.contains("run"));
+}
+
+#[test]
+fn test_deserialize_unresolved_reference() {
+    let config = load("test", &["src/lib/tests/interpolation/unresolved"])
+        .unwrap();
+
+    let port: u16 = config.deserialize("archive.port").unwrap();
+    assert_eq!(port, 8443);
+
+    let error = config.deserialize::<String>("broken.reference").unwrap_err();
+    assert_eq!(error.key.as_deref(), Some("broken.reference"));
+    assert!(error.message.contains("${missing.key}"));
+}
+
+#[test]
+fn test_deserialize_prefix_changes_length_in_upper_case() {
+    let config = load("test", &["src/lib/tests/case"]).unwrap();
+
+    let items: BTreeMap<String, String> = config.deserialize("ITEMS").unwrap();
+    assert_eq!(items.keys().collect::<Vec<_>>(), vec!["size", "ŉame"]);
+}
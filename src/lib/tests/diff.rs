@@ -0,0 +1,111 @@
+#[allow(unused_imports)]
+use std::collections::HashMap;
+#[allow(unused_imports)]
+use crate::config::diff::ConfigDiff;
+#[allow(unused_imports)]
+use super::load;
+
+#[allow(dead_code)]
+fn map(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries.iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_diff_maps() {
+    let old_options = map(&[("A.B", "1"), ("A.C", "2"), ("A.D", "3")]);
+    let old_locations = map(&[("A.B", "old.properties"),
+        ("A.C", "old.properties"), ("A.D", "old.properties")]);
+    let new_options = map(&[("A.B", "1"), ("A.C", "4"), ("A.E", "5")]);
+    let new_locations = map(&[("A.B", "new.properties"),
+        ("A.C", "new.properties"), ("A.E", "new.properties")]);
+
+    let diff = ConfigDiff::from_maps(&old_options, &old_locations,
+        &new_options, &new_locations);
+
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].key, "A.E");
+    assert_eq!(diff.added[0].old_value, None);
+    assert_eq!(diff.added[0].new_location.as_deref(), Some("new.properties"));
+
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].key, "A.D");
+    assert_eq!(diff.removed[0].new_value, None);
+    assert_eq!(diff.removed[0].old_location.as_deref(), Some("old.properties"));
+
+    // Properties that only moved to another file have the same value
+    let changed = diff.get(&"a.c").unwrap();
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(changed.old_value.as_deref(), Some("2"));
+    assert_eq!(changed.new_value.as_deref(), Some("4"));
+    assert_eq!(changed.old_location.as_deref(), Some("old.properties"));
+    assert_eq!(changed.new_location.as_deref(), Some("new.properties"));
+    assert!(diff.get(&"a.b").is_none());
+}
+
+#[test]
+fn test_empty_diff() {
+    let options = map(&[("A.B", "1")]);
+    let locations = map(&[("A.B", "test.properties")]);
+
+    let diff = ConfigDiff::from_maps(&options, &locations, &options,
+        &locations);
+    assert!(diff.is_empty());
+    assert_eq!(diff.changes().count(), 0);
+}
+
+#[test]
+fn test_diff_keys_in_any_case() {
+    let old_options = map(&[("a.Host", "1")]);
+    let old_locations = map(&[("a.Host", "old.properties")]);
+    let new_options = map(&[("A.host", "2")]);
+    let new_locations = map(&[("A.host", "new.properties")]);
+
+    let diff = ConfigDiff::from_maps(&old_options, &old_locations,
+        &new_options, &new_locations);
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].key, "A.host");
+    assert_eq!(diff.changed[0].old_value.as_deref(), Some("1"));
+    assert_eq!(diff.changed[0].new_value.as_deref(), Some("2"));
+}
+
+#[test]
+fn test_diff_profiles() {
+    let base = load("base", &["src/lib/tests/profiles/one"]).unwrap();
+    let test = load("test", &["src/lib/tests/profiles/one"]).unwrap();
+
+    let diff = base.diff(&test);
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+
+    let host = diff.get(&"archive.host").unwrap();
+    assert_eq!(host.old_value.as_deref(), Some("base.example.org"));
+    assert_eq!(host.new_value.as_deref(), Some("test.example.org"));
+    assert_eq!(host.old_location.as_deref(), Some("base.properties"));
+    assert_eq!(host.new_location.as_deref(), Some("test.properties"));
+    assert!(test.diff(&test).is_empty());
+}
+
+#[test]
+fn test_diff_paths() {
+    let one = load("base", &["src/lib/tests/profiles/one"]).unwrap();
+    let two = load("base", &["src/lib/tests/profiles/two"]).unwrap();
+
+    let diff = one.diff(&two);
+    let removed: Vec<&str> = diff.removed.iter()
+        .map(|c| c.key.as_str())
+        .collect();
+    assert_eq!(removed, vec!["archive.host", "archive.user"]);
+    assert!(diff.added.is_empty());
+    assert_eq!(diff.get(&"archive.port").unwrap().new_value.as_deref(),
+        Some("9090"));
+
+    // Comparing the other way round swaps what was added and removed
+    let reverse = two.diff(&one);
+    assert_eq!(reverse.added.len(), 2);
+    assert!(reverse.removed.is_empty());
+}
@@ -0,0 +1,186 @@
+#[allow(unused_imports)]
+use std::{
+    collections::HashMap,
+    fs,
+};
+#[allow(unused_imports)]
+use crate::config::document::{
+    PropertiesDocument,
+    PropertiesLine,
+};
+
+#[allow(dead_code)]
+const FILE: &str = "src/lib/tests/document/test.properties";
+
+#[test]
+fn test_round_trip() {
+    let bytes = fs::read(FILE).unwrap();
+    let document = PropertiesDocument::from_file(FILE).unwrap();
+    assert_eq!(document.to_bytes(), bytes);
+
+    let crlf = "# comment\r\na = 1\r\nb = 2 \\\r\n  3";
+    assert_eq!(PropertiesDocument::parse(crlf).to_string(), crlf);
+    assert_eq!(PropertiesDocument::parse("").to_string(), "");
+}
+
+#[test]
+fn test_values_match_java_properties() {
+    let bytes = fs::read(FILE).unwrap();
+    let expected: HashMap<String, String> =
+        java_properties::read(bytes.as_slice()).unwrap();
+    let document = PropertiesDocument::from_bytes(&bytes);
+
+    assert_eq!(document.keys().len(), expected.len());
+    for (key, value) in &expected {
+        assert_eq!(document.get(key).as_ref(), Some(value), "{}", key);
+    }
+
+    assert_eq!(document.get("MULTI.LINE"), Some(String::from("one, two, three")));
+    assert_eq!(document.get("latin1"), Some(String::from("caf\u{e9}")));
+    assert!(document.get("not.continued").is_some());
+
+    // Chars outside the BMP are surrogate pairs, as java.util.Properties
+    // writes them
+    let mut document = PropertiesDocument::parse("emoji = \\uD83D\\uDE00");
+    assert_eq!(document.get("emoji"), Some(String::from("\u{1F600}")));
+    document.insert("emoji", "\u{1F642}");
+    assert_eq!(document.to_string(), "emoji = \\uD83D\\uDE42");
+}
+
+#[test]
+fn test_lines() {
+    let document = PropertiesDocument::from_file(FILE).unwrap();
+    let lines = document.lines();
+
+    assert!(matches!(&lines[1], PropertiesLine::Comment(c)
+        if c == "! This comment starts with a bang"));
+    assert!(matches!(&lines[2], PropertiesLine::Blank(_)));
+
+    match &lines[5] {
+        PropertiesLine::Entry(e) => {
+            assert_eq!(e.key(), "section1.database.host");
+            assert_eq!(e.separator(), "   ");
+            assert_eq!(e.value(), "archive.example.org");
+        },
+        _ => panic!("expected an entry"),
+    };
+
+    // Lines count from 1, and a setting spread over lines starts on its first
+    assert_eq!(document.line_of("section1.database.user"), Some(4));
+    assert_eq!(document.line_of("tabs"), Some(15));
+    assert_eq!(document.line_of("duplicate"), Some(23));
+    assert_eq!(document.line_of("missing"), None);
+}
+
+#[test]
+fn test_edits_only_change_their_lines() {
+    let original = fs::read_to_string("src/lib/tests/document/test.properties")
+        .unwrap_or_default();
+    let mut document = PropertiesDocument::from_file(FILE).unwrap();
+
+    assert_eq!(document.insert("SECTION1.DATABASE.PASSWORD", "new: secret"),
+        Some(String::from("secret")));
+    assert!(document.update("multi.line", "one line"));
+    assert!(!document.update("missing.key", "value"));
+    assert_eq!(document.remove("duplicate"), Some(String::from("second")));
+    assert_eq!(document.insert("new key=", " leading space\n"), None);
+    assert_eq!(document.insert("bare.key", "value"), Some(String::new()));
+
+    let edited = document.to_string();
+    assert!(edited.contains("section1.database.password:new: secret\n"));
+    assert!(edited.contains("multi.line = one line\nempty.value =\n"));
+    assert!(!edited.contains("duplicate"));
+    assert!(edited.contains("\nbare.key = value\n"));
+    assert!(edited.ends_with("new\\ key\\= = \\ leading space\\n\n"));
+
+    // Every other line is kept as it was
+    let kept: Vec<&str> = original.lines()
+        .filter(|l| !l.starts_with("section1.database.password")
+            && !l.starts_with("multi.line")
+            && !l.starts_with("    two")
+            && !l.starts_with("    three")
+            && !l.starts_with("duplicate")
+            && !l.starts_with("bare.key")
+            && !l.starts_with("latin1"))
+        .collect();
+    for line in kept {
+        assert!(edited.lines().any(|l| l == line), "{}", line);
+    }
+
+    let reread = PropertiesDocument::parse(&edited);
+    assert_eq!(reread.get("new key="), Some(String::from(" leading space\n")));
+    assert_eq!(reread.get("section1.database.password"),
+        Some(String::from("new: secret")));
+}
+
+#[test]
+fn test_backslash_at_end_of_file() {
+    // java.util.Properties drops a backslash continuing past the last line
+    let mut document = PropertiesDocument::parse("a.b\\");
+    assert_eq!(document.keys(), vec![String::from("a.b")]);
+    assert_eq!(document.get("a.b"), Some(String::new()));
+    assert_eq!(document.to_string(), "a.b\\");
+
+    document.insert("a.b", "c");
+    assert_eq!(document.to_string(), "a.b = c");
+
+    let document = PropertiesDocument::parse("key = value\\");
+    assert_eq!(document.get("key"), Some(String::from("value")));
+}
+
+#[test]
+fn test_continued_value_start() {
+    let mut document = PropertiesDocument::parse("key = \\\n    value\n");
+    assert_eq!(document.get("key"), Some(String::from("value")));
+
+    document.insert("key", "changed");
+    assert_eq!(document.to_string(), "key = \\\n    changed\n");
+    assert_eq!(document.get("key"), Some(String::from("changed")));
+}
+
+#[test]
+fn test_insert_after_backslash_at_end_of_file() {
+    // The backslash is dropped so the new setting isn't read into `b`
+    let mut document = PropertiesDocument::parse("a = 1\nb = two\\");
+    document.insert("c", "3");
+    assert_eq!(document.to_string(), "a = 1\nb = two\nc = 3");
+
+    let written = document.to_string();
+    let expected: HashMap<String, String> =
+        java_properties::read(written.as_bytes()).unwrap();
+    assert_eq!(expected.get("b").map(String::as_str), Some("two"));
+    assert_eq!(expected.get("c").map(String::as_str), Some("3"));
+
+    let reread = PropertiesDocument::parse(&written);
+    assert_eq!(reread.get("b"), Some(String::from("two")));
+    assert_eq!(reread.get("c"), Some(String::from("3")));
+
+    let mut document = PropertiesDocument::parse("b = two\\\n");
+    document.insert("c", "3");
+    assert_eq!(document.to_string(), "b = two\nc = 3\n");
+}
+
+#[test]
+fn test_line_terminators_are_kept() {
+    // Each untouched line keeps its own terminator, and new lines get the
+    // one most lines have
+    let mut document = PropertiesDocument::parse("a = 1\r\nb = 2\nc=3\r\n");
+    document.insert("a", "9");
+    assert_eq!(document.to_string(), "a = 9\r\nb = 2\nc=3\r\n");
+    document.insert("d", "4");
+    assert_eq!(document.to_string(), "a = 9\r\nb = 2\nc=3\r\nd=4\r\n");
+
+    // A lone carriage return ends a line too
+    let text = "a = 1\rb = 2 \\\r  3\r";
+    let mut document = PropertiesDocument::parse(text);
+    assert_eq!(document.to_string(), text);
+    assert_eq!(document.get("b"), Some(String::from("2 3")));
+    assert_eq!(document.line_of("b"), Some(2));
+    document.insert("b", "4");
+    assert_eq!(document.to_string(), "a = 1\rb = 4\r");
+
+    // Removing the last line keeps the file ending without a terminator
+    let mut document = PropertiesDocument::parse("a = 1\nb = 2");
+    document.remove("b");
+    assert_eq!(document.to_string(), "a = 1");
+}
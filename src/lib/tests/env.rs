@@ -0,0 +1,73 @@
+#[allow(unused_imports)]
+use std::path::PathBuf;
+#[allow(unused_imports)]
+use super::{
+    builder,
+    load,
+};
+
+#[test]
+fn test_env_overrides() {
+    let config = builder("test", &["src/lib/tests/env"])
+        .override_vars([("CAPO__SECTION1__DATABASE__USER", "alice"),
+            ("CAPO__SERVER__MAX_CONNECTIONS", "20"), ("OTHER", "ignored")])
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get(&"section1.database.user"),
+        Some(String::from("alice")));
+    assert_eq!(config.get_location(&"section1.database.user"),
+        Some(String::from("env:CAPO__SECTION1__DATABASE__USER")));
+
+    // Overrides are read before references are resolved
+    assert_eq!(config.get(&"section1.database.password"),
+        Some(String::from("alice-password")));
+
+    // An underscore matches a dash and the key keeps its spelling
+    assert_eq!(config.get_u32(&"server.max-connections"), Some(20));
+    assert_eq!(config.get_location(&"server.max-connections"),
+        Some(String::from("env:CAPO__SERVER__MAX_CONNECTIONS")));
+
+    let history = config.get_history(&"section1.database.user").unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].value, "alice");
+    assert_eq!(history[1].file, PathBuf::new());
+}
+
+#[test]
+fn test_env_adds_keys() {
+    let config = builder("test", &["src/lib/tests/env"])
+        .override_vars([("CAPO__ARCHIVE__HOST", "archive.example.org")])
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get_key(&"ARCHIVE.HOST"),
+        Some(String::from("archive.host")));
+    assert_eq!(config.get(&"archive.host"),
+        Some(String::from("archive.example.org")));
+}
+
+#[test]
+fn test_env_prefix() {
+    let vars = [("CAPO__SECTION1__DATABASE__USER", "alice"),
+        ("MYAPP_SECTION1__DATABASE__USER", "bob")];
+    let env = builder("test", &["src/lib/tests/env"]).override_vars(vars);
+
+    let config = env.clone().override_prefix("MYAPP_").build().unwrap();
+    assert_eq!(config.get(&"section1.database.user"),
+        Some(String::from("bob")));
+
+    // An empty prefix turns the overrides off
+    let config = env.override_prefix("").build().unwrap();
+    assert_eq!(config.get(&"section1.database.user"),
+        Some(String::from("user")));
+}
+
+#[test]
+fn test_env_ignored() {
+    // Nothing is read from the process environment, whatever it holds
+    let config = load("test", &["src/lib/tests/env"]).unwrap();
+
+    assert_eq!(config.get(&"section1.database.user"),
+        Some(String::from("user")));
+}
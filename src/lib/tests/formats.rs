@@ -0,0 +1,123 @@
+#[allow(unused_imports)]
+use std::{
+    collections::HashMap,
+    io::BufRead,
+    path::Path,
+};
+#[allow(unused_imports)]
+use crate::config::source::ConfigSource;
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoError;
+#[allow(unused_imports)]
+use super::builder;
+
+/// A format of `key: value` lines, to test formats outside the crate
+#[allow(dead_code)]
+#[derive(Debug)]
+struct KeyValueSource;
+
+impl ConfigSource for KeyValueSource {
+    fn extensions(&self) -> &[&str] {
+        &["kv"]
+    }
+
+    fn read(&self, reader: &mut dyn BufRead, file: &Path)
+        -> Result<HashMap<String, String>, CapoError> {
+
+        let mut options = HashMap::new();
+        for line in reader.lines() {
+            let line = line.map_err(|source| CapoError::Io {
+                file: file.to_path_buf(),
+                source,
+            })?;
+
+            if let Some((key, value)) = line.split_once(':') {
+                options.insert(key.trim().to_string(),
+                    value.trim().to_string());
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[test]
+fn test_custom_format() {
+    let config = builder("test", &["src/lib/tests/formats"])
+        .format(KeyValueSource)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get(&"kv.db.user"), Some(String::from("casa")));
+    assert_eq!(config.get_location(&"kv.db.user"),
+        Some(String::from("test.kv")));
+    assert_eq!(config.get(&"archive.port"), Some(String::from("8080")));
+}
+
+#[test]
+fn test_extended_profile_formats() {
+    let config = builder("child", &["src/lib/tests/formats"])
+        .format(KeyValueSource)
+        .build()
+        .unwrap();
+
+    // The extended profile is layered from every format, like a profile on
+    // the path
+    assert_eq!(config.get(&"child.key"), Some(String::from("child")));
+    assert_eq!(config.get(&"archive.port"), Some(String::from("8080")));
+    assert_eq!(config.get(&"kv.db.user"), Some(String::from("casa")));
+    assert_eq!(config.get_profile(&"kv.db.user"), Some(String::from("test")));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_format() {
+    let config = builder("test", &["src/lib/tests/formats"]).build().unwrap();
+
+    // Later formats override .properties in the same directory
+    assert_eq!(config.get(&"archive.host"),
+        Some(String::from("toml.example.org")));
+    assert_eq!(config.get_location(&"archive.host"),
+        Some(String::from("test.toml")));
+    assert_eq!(config.get(&"archive.port"), Some(String::from("8080")));
+
+    assert_eq!(config.get(&"toml.db.user"), Some(String::from("casa")));
+    assert_eq!(config.get_u8(&"toml.db.retries"), Some(3));
+    assert_eq!(config.get_bool(&"toml.db.secure"), Some(true));
+    assert_eq!(config.get_indexed_list::<String, _>(&"toml.db.hosts"),
+        Ok(Some(vec![String::from("alpha"), String::from("beta")])));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_format() {
+    let config = builder("test", &["src/lib/tests/formats"]).build().unwrap();
+
+    assert_eq!(config.get(&"yaml.db.user"), Some(String::from("casa")));
+    assert_eq!(config.get_u8(&"yaml.db.retries"), Some(3));
+    assert_eq!(config.get(&"yaml.db.hosts.1"), Some(String::from("beta")));
+    assert_eq!(config.get_location(&"yaml.db.user"),
+        Some(String::from("test.yaml")));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_format() {
+    let config = builder("test", &["src/lib/tests/formats"]).build().unwrap();
+
+    assert_eq!(config.get(&"json.db.user"), Some(String::from("casa")));
+    assert_eq!(config.get_u8(&"json.db.retries"), Some(3));
+    assert_eq!(config.get(&"json.db.hosts.0"), Some(String::from("alpha")));
+    assert_eq!(config.get(&"json.db.missing"), None);
+}
+
+#[cfg(feature = "ini")]
+#[test]
+fn test_ini_format() {
+    let config = builder("test", &["src/lib/tests/formats"]).build().unwrap();
+
+    assert_eq!(config.get(&"ini.db.user"), Some(String::from("casa")));
+    assert_eq!(config.get(&"top"), Some(String::from("level")));
+    assert_eq!(config.get_location(&"ini.db.retries"),
+        Some(String::from("test.ini")));
+}
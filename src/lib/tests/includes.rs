@@ -0,0 +1,67 @@
+#[allow(unused_imports)]
+use std::{
+    fs,
+    path::PathBuf,
+};
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoError;
+#[allow(unused_imports)]
+use super::load;
+#[allow(unused_imports)]
+use super::common::{
+    scratch_dir,
+    write_file,
+};
+
+#[test]
+fn test_includes() {
+    let config = load("test", &["src/lib/tests/includes"]).unwrap();
+
+    // The including file overrides the files it includes
+    assert_eq!(config.get(&"db.user"), Some(String::from("test")));
+    assert_eq!(config.get_location(&"db.user"),
+        Some(String::from("test.properties")));
+
+    assert_eq!(config.get(&"db.password"), Some(String::from("secret")));
+    assert_eq!(config.get_location(&"db.password"),
+        Some(String::from("db.properties")));
+    assert_eq!(config.get_profile(&"db.password"), Some(String::from("test")));
+
+    let history = config.get_history(&"db.password").unwrap();
+    assert_eq!(history[0].file,
+        PathBuf::from("src/lib/tests/includes/common/db.properties"));
+
+    // Glob matches are read in alphabetical order
+    assert_eq!(config.get(&"extra.a"), Some(String::from("1")));
+    assert_eq!(config.get(&"extra.order"), Some(String::from("b")));
+
+    assert_eq!(config.get(&"capo.include"), None);
+}
+
+#[test]
+fn test_include_cycle() {
+    match load("test", &["src/lib/tests/includes/cycle"]) {
+        Err(CapoError::IncludeCycle { chain }) => {
+            assert_eq!(chain.len(), 3);
+            assert!(chain[0].ends_with("test.properties"));
+            assert!(chain[1].ends_with("other.properties"));
+            assert!(chain[2].ends_with("test.properties"));
+        },
+        _ => panic!("expected an IncludeCycle error"),
+    }
+}
+
+#[test]
+fn test_include_pattern_in_glob_directory() {
+    // The directory's name would be a character class if it were a pattern
+    let dir = scratch_dir("includes").join("[profiles]");
+    fs::create_dir_all(dir.join("extra")).unwrap();
+    write_file(&dir.join("test.properties"),
+        "capo.include = extra/*.properties\n", 1);
+    write_file(&dir.join("extra/a.properties"), "extra.a = 1\n", 1);
+
+    let config = load("test", &[&dir]).unwrap();
+    assert_eq!(config.get(&"extra.a"), Some(String::from("1")));
+
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
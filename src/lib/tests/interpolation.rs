@@ -0,0 +1,121 @@
+#[allow(unused_imports)]
+use std::env;
+#[allow(unused_imports)]
+use crate::helpers::errors::{
+    CapoError,
+    CapoValueError,
+};
+#[allow(unused_imports)]
+use super::{
+    builder,
+    load,
+};
+
+#[test]
+fn test_key_references() {
+    let config = load("test", &["src/lib/tests/interpolation"]).unwrap();
+
+    assert_eq!(config.get(&"archive.url").unwrap(),
+        "https://archive.nrao.edu/archive");
+    assert_eq!(config.get(&"archive.root").unwrap(), "/data/archive");
+    assert_eq!(config.get(&"archive.cache").unwrap(), "/data/archive/cache");
+}
+
+#[test]
+fn test_env_references() {
+    let config = load("test", &["src/lib/tests/interpolation"]).unwrap();
+
+    assert_eq!(config.get(&"archive.path").unwrap(), env::var("PATH").unwrap());
+}
+
+#[test]
+fn test_escaped_references() {
+    let config = load("test", &["src/lib/tests/interpolation"]).unwrap();
+
+    assert_eq!(config.get(&"archive.literal").unwrap(), "${base.dir}");
+    assert_eq!(config.get(&"archive.price").unwrap(), "$5");
+}
+
+#[test]
+fn test_raw_retrieval() {
+    let config = load("test", &["src/lib/tests/interpolation"]).unwrap();
+
+    assert_eq!(config.get_raw(&"archive.cache").unwrap(),
+        "${archive.root}/cache");
+    assert_eq!(config.get_raw(&"archive.literal").unwrap(), "$${base.dir}");
+}
+
+#[test]
+fn test_references_across_files() {
+    let config = builder("test", &["src/lib/tests/interpolation"])
+        .source("src/lib/tests/interpolation/override.properties")
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get(&"archive.cache").unwrap(),
+        "/override/archive/cache");
+}
+
+#[test]
+fn test_reference_cycle() {
+    let config = load("test", &["src/lib/tests/interpolation/cycle"]).unwrap();
+
+    assert_eq!(config.get(&"cycle.a").unwrap(), "${cycle.b}");
+    match config.get_unresolved(&"cycle.a") {
+        Some(CapoError::InterpolationCycle { chain }) => {
+            assert_eq!(chain.len(), 4);
+            assert_eq!(chain.first(), chain.last());
+        },
+        _ => panic!("expected an InterpolationCycle error"),
+    }
+
+    match builder("test", &["src/lib/tests/interpolation/cycle"])
+        .strict(true)
+        .build() {
+
+        Err(CapoError::InterpolationCycle { .. }) => {},
+        _ => panic!("expected an InterpolationCycle error"),
+    }
+}
+
+#[test]
+fn test_unresolved_reference() {
+    let config = load("test", &["src/lib/tests/interpolation/unresolved"])
+        .unwrap();
+
+    // Other keys are still read
+    assert_eq!(config.get_u16(&"archive.port"), Some(8443));
+
+    // The broken value is kept as written and reported when it's parsed
+    assert_eq!(config.get(&"broken.reference").unwrap(),
+        "${missing.key}/archive");
+    match config.get_as::<String, _>(&"broken.reference") {
+        Err(CapoValueError::Invalid { value, reason, .. }) => {
+            assert_eq!(value, "${missing.key}/archive");
+            assert!(reason.contains("${missing.key}"));
+        },
+        other => panic!("expected an invalid value, got {:?}", other),
+    };
+
+    // A value using the broken one is reported the same way
+    assert_eq!(config.get(&"broken.dependent").unwrap(),
+        "${broken.reference}/cache");
+    match config.get_unresolved(&"broken.dependent") {
+        Some(CapoError::UnresolvedReference { key, reference }) => {
+            assert_eq!(key, "broken.reference");
+            assert_eq!(reference, "${missing.key}");
+        },
+        _ => panic!("expected an UnresolvedReference error"),
+    }
+
+    match builder("test", &["src/lib/tests/interpolation/unresolved"])
+        .strict(true)
+        .build() {
+
+        Err(CapoError::UnresolvedReference { key, reference }) => {
+            assert_eq!(key, "broken.reference");
+            assert_eq!(reference, "${missing.key}");
+        },
+        _ => panic!("expected an UnresolvedReference error"),
+    }
+}
@@ -0,0 +1,80 @@
+#[allow(unused_imports)]
+use crate::config::list::ListOptions;
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoValueError;
+#[allow(unused_imports)]
+use super::load;
+
+#[test]
+fn test_get_list() {
+    let config = load("test", &["src/lib/tests/lists"]).unwrap();
+
+    assert_eq!(config.get_list::<String, _>(&"contacts.emails"), Ok(Some(vec![
+        String::from("ann@example.org"),
+        String::from("bob@example.org"),
+        String::from("carl@example.org"),
+    ])));
+    assert_eq!(config.get_list::<String, _>(&"contacts.names"), Ok(Some(vec![
+        String::from("Doe, Jane"),
+        String::from("Roe, Richard"),
+        String::from("Smith"),
+    ])));
+    assert_eq!(config.get_list::<String, _>(&"contacts.missing"), Ok(None));
+    assert!(config.get_list::<String, _>(&"contacts.broken").is_err());
+}
+
+#[test]
+fn test_get_list_with() {
+    let config = load("test", &["src/lib/tests/lists"]).unwrap();
+
+    let options = ListOptions::new().separator(';');
+    assert_eq!(config.get_list_with::<u16, _>(&"contacts.ports", &options),
+        Ok(Some(vec![8080, 8081, 8082])));
+
+    let options = ListOptions::new().skip_empty(false).trim(false);
+    assert_eq!(config.get_list_with::<String, _>(&"contacts.emails",
+        &options), Ok(Some(vec![
+        String::from("ann@example.org"),
+        String::from(" bob@example.org "),
+        String::new(),
+        String::from(" carl@example.org"),
+    ])));
+}
+
+#[test]
+fn test_list_element_errors() {
+    let config = load("test", &["src/lib/tests/lists"]).unwrap();
+
+    match config.get_list::<u8, _>(&"contacts.numbers") {
+        Err(CapoValueError::Invalid { key, value, location, type_name, .. })
+            => {
+            assert_eq!(key, "contacts.numbers[2]");
+            assert_eq!(value, "three");
+            assert_eq!(location, Some(String::from(
+                "src/lib/tests/lists/test.properties")));
+            assert_eq!(type_name, "u8");
+        },
+        other => panic!("expected an invalid element, got {:?}", other),
+    };
+
+    match config.get_indexed_list::<u16, _>(&"ports") {
+        Err(CapoValueError::Invalid { key, value, .. }) => {
+            assert_eq!(key, "ports[1]");
+            assert_eq!(value, "ssh");
+        },
+        other => panic!("expected an invalid element, got {:?}", other),
+    };
+}
+
+#[test]
+fn test_get_indexed_list() {
+    let config = load("test", &["src/lib/tests/lists"]).unwrap();
+
+    assert_eq!(config.get_indexed_list::<String, _>(&"hosts"), Ok(Some(vec![
+        String::from("alpha"),
+        String::from("beta"),
+        String::from("gamma"),
+        String::from("kappa"),
+    ])));
+    assert_eq!(config.get_indexed_list::<String, _>(&"nothing"), Ok(None));
+}
@@ -1 +1,69 @@
+//! The library's tests, with the fixture most of them load their
+//! configuration with
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use crate::config::{
+    builder::CapoConfigBuilder,
+    config::CapoConfig,
+};
+use crate::helpers::errors::CapoError;
+
+pub mod common;
 pub mod test;
+pub mod builder;
+pub mod interpolation;
+pub mod reload;
+pub mod diff;
+pub mod document;
+pub mod values;
+pub mod lists;
+pub mod section;
+pub mod case;
+pub mod profiles;
+pub mod includes;
+pub mod formats;
+pub mod env;
+pub mod overrides;
+#[cfg(test)]
+pub mod settings;
+#[cfg(all(test, feature = "serde"))]
+pub mod de;
+#[cfg(feature = "watch")]
+pub mod watch;
+
+/// Start building a configuration that reads a profile from the given
+/// directories and nothing from the environment or the user's directory
+///
+/// # Arguments
+/// * `profile` - A &str with the profile, or profiles separated by commas
+/// * `dirs` - The directories of the path, in the order they're read
+///
+/// # Return
+/// A CapoConfigBuilder that tests can change before building
+#[allow(dead_code)]
+pub fn builder<P: AsRef<Path>>(profile: &str, dirs: &[P]) -> CapoConfigBuilder {
+    CapoConfigBuilder::new()
+        .ignore_environment()
+        .append_user_dir(false)
+        .profile(profile)
+        .path(dirs.iter().map(|d| PathBuf::from(d.as_ref())).collect())
+}
+
+/// Load a configuration that reads a profile from the given directories and
+/// nothing from the environment or the user's directory
+///
+/// # Arguments
+/// * `profile` - A &str with the profile, or profiles separated by commas
+/// * `dirs` - The directories of the path, in the order they're read
+///
+/// # Return
+/// A Result with the CapoConfig or the CapoError it couldn't be loaded with
+#[allow(dead_code)]
+pub fn load<P: AsRef<Path>>(profile: &str, dirs: &[P])
+    -> Result<CapoConfig, CapoError> {
+
+    builder(profile, dirs).build()
+}
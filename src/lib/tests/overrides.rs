@@ -0,0 +1,68 @@
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoError;
+#[allow(unused_imports)]
+use super::builder;
+
+#[test]
+fn test_builder_overrides() {
+    let config = builder("test", &["src/lib/tests/env"])
+        .with_override("SECTION1.DATABASE.USER", "bob")
+        .with_override("server.timeout", "${server.max-connections}s")
+        .build()
+        .unwrap();
+
+    // Overrides keep the spelling of the key they override
+    assert_eq!(config.get_key(&"section1.database.user"),
+        Some(String::from("section1.database.user")));
+    assert_eq!(config.get(&"section1.database.user"),
+        Some(String::from("bob")));
+    assert_eq!(config.get_location(&"section1.database.user"),
+        Some(String::from("cli")));
+    assert_eq!(config.get(&"section1.database.password"),
+        Some(String::from("bob-password")));
+
+    assert_eq!(config.get_duration(&"server.timeout").unwrap(),
+        Some(std::time::Duration::from_secs(10)));
+}
+
+#[test]
+fn test_overrides_beat_environment() {
+    let config = builder("test", &["src/lib/tests/env"])
+        .override_vars([("CAPO__SECTION1__DATABASE__USER", "alice")])
+        .with_override("section1.database.user", "bob")
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get(&"section1.database.user"),
+        Some(String::from("bob")));
+
+    let locations: Vec<String> = config.get_history(&"section1.database.user")
+        .unwrap()
+        .into_iter()
+        .map(|e| e.location)
+        .collect();
+    assert_eq!(locations, vec!["test.properties",
+        "env:CAPO__SECTION1__DATABASE__USER", "cli"]);
+}
+
+#[test]
+fn test_config_overrides() {
+    let mut config = builder("test", &["src/lib/tests/env"]).build()
+        .unwrap()
+        .with_override("server.max-connections", "50")
+        .unwrap();
+
+    assert_eq!(config.get_u32(&"server.max-connections"), Some(50));
+
+    // Overrides survive a reload
+    config.reload().unwrap();
+    assert_eq!(config.get_u32(&"server.max-connections"), Some(50));
+
+    let config = config.with_override("section1.database.user", "${missing}")
+        .unwrap();
+    assert_eq!(config.get(&"section1.database.user").unwrap(), "${missing}");
+    match config.get_unresolved(&"section1.database.user") {
+        Some(CapoError::UnresolvedReference { .. }) => {},
+        _ => panic!("expected an UnresolvedReference error"),
+    }
+}
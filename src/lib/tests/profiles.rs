@@ -0,0 +1,83 @@
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoError;
+#[allow(unused_imports)]
+use super::load;
+
+#[test]
+fn test_composite_profiles() {
+    let config = load("base,test", &["src/lib/tests/profiles/one",
+        "src/lib/tests/profiles/two"]).unwrap();
+
+    assert_eq!(config.profiles(), vec![String::from("base"),
+        String::from("test")]);
+    assert_eq!(config.get(&"archive.host"),
+        Some(String::from("test.example.org")));
+    assert_eq!(config.get_profile(&"archive.host"), Some(String::from("test")));
+
+    // Every profile is layered in a directory before the next directory
+    assert_eq!(config.get(&"archive.port"), Some(String::from("9090")));
+    assert_eq!(config.get_profile(&"archive.port"), Some(String::from("base")));
+
+    // base is only read once in a directory, even though test extends it
+    let history = config.get_history(&"archive.host").unwrap();
+    let profiles: Vec<Option<String>> = history.iter()
+        .map(|h| h.profile.clone())
+        .collect();
+    assert_eq!(profiles, vec![Some(String::from("base")),
+        Some(String::from("test"))]);
+}
+
+#[test]
+fn test_later_profile_overrides_its_child() {
+    // test extends base, but base is listed after it and so layered over it
+    let config = load("test,base", &["src/lib/tests/profiles/one"]).unwrap();
+
+    assert_eq!(config.get(&"archive.host"),
+        Some(String::from("base.example.org")));
+    assert_eq!(config.get_profile(&"archive.host"), Some(String::from("base")));
+
+    let profiles: Vec<Option<String>> = config.get_history(&"archive.host")
+        .unwrap()
+        .iter()
+        .map(|h| h.profile.clone())
+        .collect();
+    assert_eq!(profiles, vec![Some(String::from("test")),
+        Some(String::from("base"))]);
+}
+
+#[test]
+fn test_available_profiles() {
+    let config = load("test", &["src/lib/tests/profiles/one",
+        "src/lib/tests/profiles/two"]).unwrap();
+
+    assert_eq!(config.available_profiles(), vec!["base", "cycle", "loop",
+        "staging", "test"]);
+}
+
+#[test]
+fn test_extended_profiles() {
+    let config = load("staging", &["src/lib/tests/profiles/one"]).unwrap();
+
+    assert_eq!(config.get(&"archive.user"), Some(String::from("staging")));
+    assert_eq!(config.get(&"archive.host"),
+        Some(String::from("test.example.org")));
+    assert_eq!(config.get(&"archive.port"), Some(String::from("8080")));
+    assert_eq!(config.get_profile(&"archive.port"), Some(String::from("base")));
+    assert_eq!(config.get_location(&"archive.port"),
+        Some(String::from("base.properties")));
+
+    // The directive isn't a property
+    assert_eq!(config.get(&"capo.profile.extends"), None);
+    assert_eq!(config.options.len(), 3);
+}
+
+#[test]
+fn test_profile_cycle() {
+    match load("loop", &["src/lib/tests/profiles/one"]) {
+        Err(CapoError::ProfileCycle { chain }) => {
+            assert_eq!(chain, vec![String::from("loop"),
+                String::from("cycle"), String::from("loop")]);
+        },
+        _ => panic!("expected a ProfileCycle error"),
+    }
+}
@@ -0,0 +1,97 @@
+#[allow(unused_imports)]
+use std::fs;
+#[allow(unused_imports)]
+use super::{
+    builder,
+    load,
+};
+#[allow(unused_imports)]
+use super::common::{
+    scratch_dir,
+    write_file,
+};
+
+#[test]
+fn test_refresh_without_changes() {
+    let dir = scratch_dir("refresh-unchanged");
+    write_file(&dir.join("test.properties"), "section1.user = user\n", 1000);
+
+    let mut config = load("test", &[&dir]).unwrap();
+    assert!(!config.refresh_if_changed().unwrap());
+    assert_eq!(config.get(&"section1.user").unwrap(), "user");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_refresh_changed_file() {
+    let dir = scratch_dir("refresh-changed");
+    write_file(&dir.join("test.properties"), "section1.user = user\n", 1000);
+
+    let mut config = load("test", &[&dir]).unwrap();
+    write_file(&dir.join("test.properties"), "section1.user = admin\n", 2000);
+
+    assert!(config.refresh_if_changed().unwrap());
+    assert_eq!(config.get(&"section1.user").unwrap(), "admin");
+    assert!(!config.refresh_if_changed().unwrap());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_refresh_new_and_removed_files() {
+    let dir = scratch_dir("refresh-new");
+    let (first, second) = (dir.join("first"), dir.join("second"));
+    fs::create_dir_all(&first).unwrap();
+    fs::create_dir_all(&second).unwrap();
+    write_file(&first.join("test.properties"), "section1.user = user\n", 1000);
+
+    let mut config = load("test", &[&first, &second]).unwrap();
+    assert_eq!(config.cfg_files.len(), 1);
+
+    // A file that didn't exist when the config was loaded is picked up
+    write_file(&second.join("test.properties"),
+        "section1.user = admin\nsection1.password = secret\n", 1000);
+    assert!(config.refresh_if_changed().unwrap());
+    assert_eq!(config.cfg_files.len(), 2);
+    assert_eq!(config.get(&"section1.user").unwrap(), "admin");
+    assert_eq!(config.get_history(&"section1.user").unwrap().len(), 2);
+
+    // And its properties disappear again when it's removed
+    fs::remove_file(second.join("test.properties")).unwrap();
+    assert!(config.refresh_if_changed().unwrap());
+    assert_eq!(config.get(&"section1.user").unwrap(), "user");
+    assert!(config.get(&"section1.password").is_none());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_refresh_keeps_config_on_error() {
+    let dir = scratch_dir("refresh-error");
+    write_file(&dir.join("test.properties"), "section1.user = user\n", 1000);
+
+    let mut config = builder("test", &[&dir]).strict(true).build().unwrap();
+    write_file(&dir.join("test.properties"),
+        "section1.user = ${section1.user}\n", 2000);
+
+    assert!(config.refresh_if_changed().is_err());
+    assert_eq!(config.get(&"section1.user").unwrap(), "user");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_reload() {
+    let dir = scratch_dir("reload");
+    write_file(&dir.join("test.properties"), "section1.user = user\n", 1000);
+
+    let mut config = load("test", &[&dir]).unwrap();
+
+    // A full reload doesn't depend on the modification time changing
+    write_file(&dir.join("test.properties"), "section1.user = admin\n", 1000);
+    config.reload().unwrap();
+    assert_eq!(config.get(&"section1.user").unwrap(), "admin");
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,58 @@
+#[allow(unused_imports)]
+use super::load;
+
+#[test]
+fn test_section_getters() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+    let program = config.section(&"section2.programA");
+
+    assert_eq!(program.prefix(), "section2.programA");
+    assert_eq!(program.get_bool(&"run"), Some(true));
+    assert_eq!(program.get(&"output"),
+        Some(String::from("/testing/resources")));
+    assert_eq!(program.get_location(&"run"),
+        Some(String::from("test.properties")));
+    assert_eq!(program.get(&"contactList"), None);
+
+    let integers = config.section(&"section3.integer.");
+    assert_eq!(integers.require::<u8, _>(&"hundred"), Ok(100));
+    assert_eq!(integers.get_or(&"ten", 10u8), Ok(10));
+}
+
+#[test]
+fn test_section_children() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+
+    assert_eq!(config.section(&"section2").children(),
+        vec![String::from("programA"), String::from("programB")]);
+    assert_eq!(config.section(&"section2").section(&"programB").children(),
+        vec![String::from("contactList"), String::from("run")]);
+    assert_eq!(config.section(&"").children(), vec![
+        String::from("section1"),
+        String::from("section2"),
+        String::from("section3"),
+        String::from("section4"),
+    ]);
+    assert!(config.section(&"section9").children().is_empty());
+}
+
+#[test]
+fn test_section_children_change_length_in_upper_case() {
+    // `ı` and `ŉ`, escaped in the file, take a different number of bytes
+    // once upper cased
+    let config = load("test", &["src/lib/tests/case"]).unwrap();
+
+    assert_eq!(config.section(&"ıtems").children(),
+        vec![String::from("size"), String::from("ŉame")]);
+    assert_eq!(config.section(&"ITEMS").children(),
+        vec![String::from("size"), String::from("ŉame")]);
+    assert_eq!(config.section(&"II").children(), vec![String::from("ab")]);
+    assert_eq!(config.section(&"ıtems.ŉame").children(), Vec::<String>::new());
+
+    // Indexed lists match the prefix the same way
+    let nodes = vec![String::from("alpha"), String::from("beta")];
+    assert_eq!(config.get_indexed_list::<String, _>(&"ŉodes"),
+        Ok(Some(nodes.clone())));
+    assert_eq!(config.get_indexed_list::<String, _>(&"ʼNODES"),
+        Ok(Some(nodes)));
+}
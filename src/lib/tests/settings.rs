@@ -0,0 +1,164 @@
+#![allow(clippy::approx_constant)]
+
+#[allow(unused_imports)]
+use std::{
+    path::PathBuf,
+    time::Duration,
+};
+
+#[allow(unused_imports)]
+use rustcapo_derive::CapoSettings;
+#[allow(unused_imports)]
+use url::Url;
+#[allow(unused_imports)]
+use crate::config::settings::{
+    CapoSettingProblem,
+    CapoSettings,
+};
+#[allow(unused_imports)]
+use crate::helpers::values::{
+    parse_duration,
+    ByteSize,
+};
+#[allow(unused_imports)]
+use super::load;
+
+#[allow(dead_code)]
+#[derive(Debug, CapoSettings)]
+#[capo(prefix = "section1.database")]
+struct Database {
+    /// The user to connect as
+    user: String,
+    #[capo(key = "password", doc = "The user's password")]
+    secret: String,
+    #[capo(default = "5432")]
+    port: u16,
+    host: Option<String>,
+    #[capo(optional)]
+    retries: u32,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, CapoSettings)]
+struct Numbers {
+    #[capo(key = "section3.integer.hundred")]
+    hundred: u8,
+    #[capo(key = "section4.float.pi")]
+    pi: f64,
+    #[capo(key = "section2.programA.run")]
+    run: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, CapoSettings)]
+#[capo(prefix = "server")]
+struct Server {
+    host: String,
+    port: u16,
+    secure: bool,
+    #[capo(required)]
+    name: Option<String>,
+    timeout: u32,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, CapoSettings)]
+#[capo(prefix = "archive")]
+struct Archive {
+    timeout: Duration,
+    cache: ByteSize,
+    root: PathBuf,
+    url: Url,
+    compress: bool,
+    verify: bool,
+    #[capo(key = "deadline")]
+    due: Option<Duration>,
+}
+
+#[test]
+fn test_load_settings() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+
+    let database = Database::from_capo(&config).unwrap();
+    assert_eq!(database.user, "user");
+    assert_eq!(database.secret, "password");
+    assert_eq!(database.port, 5432);
+    assert_eq!(database.host, None);
+    assert_eq!(database.retries, 0);
+
+    let numbers = Numbers::from_capo(&config).unwrap();
+    assert_eq!(numbers.hundred, 100);
+    assert_eq!(numbers.pi, 3.1415);
+    assert!(numbers.run);
+}
+
+#[test]
+fn test_settings_report_every_problem() {
+    let config = load("test", &["src/lib/tests/settings"]).unwrap();
+
+    let error = Server::from_capo(&config).unwrap_err();
+    assert_eq!(error.problems, vec![
+        CapoSettingProblem::Malformed {
+            key: String::from("server.port"),
+            value: String::from("eighty"),
+            location: String::from("src/lib/tests/settings/test.properties"),
+            type_name: String::from("u16"),
+            reason: String::from("invalid digit found in string"),
+        },
+        CapoSettingProblem::Malformed {
+            key: String::from("server.secure"),
+            value: String::from("maybe"),
+            location: String::from("src/lib/tests/settings/test.properties"),
+            type_name: String::from("bool"),
+            reason: String::from("expected yes, no, true or false"),
+        },
+        CapoSettingProblem::Missing {
+            key: String::from("server.name"),
+        },
+    ]);
+}
+
+#[test]
+fn test_settings_match_getters() {
+    let config = load("test", &["src/lib/tests/values"]).unwrap();
+
+    let error = Archive::from_capo(&config).unwrap_err();
+    assert_eq!(error.problems.len(), 1);
+    match &error.problems[0] {
+        CapoSettingProblem::Malformed { key, type_name, reason, .. } => {
+            assert_eq!(key, "archive.deadline");
+            assert_eq!(type_name, "Duration");
+            assert_eq!(Err(reason.clone()), parse_duration("soon"));
+        },
+        other => panic!("expected a malformed duration, got {:?}", other),
+    };
+
+    let config = config.with_override("archive.deadline", "2d").unwrap();
+    let archive = Archive::from_capo(&config).unwrap();
+    assert_eq!(Some(archive.timeout),
+        config.get_duration(&"archive.timeout").unwrap());
+    assert_eq!(Some(archive.cache.0),
+        config.get_bytesize(&"archive.cache").unwrap());
+    assert_eq!(Some(archive.root),
+        config.get_path(&"archive.root").unwrap());
+    assert_eq!(Some(archive.url), config.get_url(&"archive.url").unwrap());
+    assert!(archive.compress);
+    assert!(!archive.verify);
+    assert_eq!(archive.due, Some(Duration::from_secs(2 * 24 * 60 * 60)));
+}
+
+#[test]
+fn test_describe_settings() {
+    let info = Database::describe();
+
+    assert_eq!(info.len(), 5);
+    assert_eq!(info[0].key, "section1.database.user");
+    assert_eq!(info[0].description, Some("The user to connect as"));
+    assert!(info[0].required);
+    assert_eq!(info[1].key, "section1.database.password");
+    assert_eq!(info[1].description, Some("The user's password"));
+    assert_eq!(info[2].default, Some("5432"));
+    assert!(!info[2].required);
+    assert_eq!(info[3].type_name, "String");
+    assert!(!info[3].required);
+}
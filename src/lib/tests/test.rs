@@ -1,7 +1,14 @@
+#![allow(clippy::approx_constant)]
 #[allow(unused_imports)]
 use std::collections::HashMap;
 #[allow(unused_imports)]
 use crate::config::config;
+#[allow(unused_imports)]
+use std::path::PathBuf;
+#[allow(unused_imports)]
+use crate::helpers::errors::CapoError;
+#[allow(unused_imports)]
+use super::builder;
 
 #[test]
 fn initialize_config() {
@@ -177,11 +184,11 @@ fn test_all_options() {
     let expected = HashMap::from([
         (String::from("section1.database.user"), String::from("user")),
         (String::from("section1.database.password"), String::from("password")),
-        (String::from("section2.programa.run"), String::from("true")),
-        (String::from("section2.programa.output"),
+        (String::from("section2.programA.run"), String::from("true")),
+        (String::from("section2.programA.output"),
             String::from("/testing/resources")),
-        (String::from("section2.programb.run"), String::from("false")),
-        (String::from("section2.programb.contactlist"),
+        (String::from("section2.programB.run"), String::from("false")),
+        (String::from("section2.programB.contactList"),
             String::from("apples@banannas.com")),
         (String::from("section3.integer.one"), String::from("1")),
         (String::from("section3.integer.hundred"), String::from("100")),
@@ -191,7 +198,7 @@ fn test_all_options() {
     let actual = config.get_options();
 
     for (key, val) in expected.iter() {
-        assert_eq!(actual.get(&key.to_uppercase()).unwrap(), val);
+        assert_eq!(actual.get(key).unwrap(), val);
     }
 
     let expected_len = 10;
@@ -218,11 +225,11 @@ fn test_all_locations() {
         (String::from("section1.database.user"),
             expected_location.clone()),
         (String::from("section1.database.password"), expected_location.clone()),
-        (String::from("section2.programa.run"), expected_location.clone()),
-        (String::from("section2.programa.output"),
+        (String::from("section2.programA.run"), expected_location.clone()),
+        (String::from("section2.programA.output"),
             expected_location.clone()),
-        (String::from("section2.programb.run"), expected_location.clone()),
-        (String::from("section2.programb.contactlist"),
+        (String::from("section2.programB.run"), expected_location.clone()),
+        (String::from("section2.programB.contactList"),
             expected_location.clone()),
         (String::from("section3.integer.one"), expected_location.clone()),
         (String::from("section3.integer.hundred"), expected_location.clone()),
@@ -232,9 +239,45 @@ fn test_all_locations() {
     let actual = config.get_locations();
 
     for (key, val) in expected.iter() {
-        assert_eq!(actual.get(&key.to_uppercase()).unwrap(), val);
+        assert_eq!(actual.get(key).unwrap(), val);
     }
 
     let expected_len = 10;
     assert_eq!(actual.len(), expected_len);
 }
+
+#[test]
+fn test_missing_files_error() {
+    let result = config::CapoConfig::new(Some("missing"),
+        Some("src/lib/tests"));
+
+    match result {
+        Err(e @ CapoError::NoFilesFound { .. }) =>
+            assert_eq!(e.status_code(), 5),
+        _ => panic!("expected a NoFilesFound error"),
+    }
+}
+
+#[test]
+fn test_history_retrieval() {
+    let config = builder("test", &["src/lib/tests"])
+        .source("src/lib/tests/extra/override.properties")
+        .build()
+        .unwrap();
+
+    let history = config.get_history(&"section1.database.user").unwrap();
+    assert_eq!(history.len(), 2);
+
+    assert_eq!(history[0].value, "user");
+    assert_eq!(history[0].location, "test.properties");
+    assert_eq!(history[0].file, PathBuf::from("src/lib/tests/test.properties"));
+
+    assert_eq!(history[1].value, "admin");
+    assert_eq!(history[1].location, "override.properties");
+    assert_eq!(history[1].file,
+        PathBuf::from("src/lib/tests/extra/override.properties"));
+
+    let history = config.get_history(&"section1.database.password").unwrap();
+    assert_eq!(history.len(), 1);
+    assert!(config.get_history(&"section9.missing").is_none());
+}
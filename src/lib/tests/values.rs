@@ -0,0 +1,175 @@
+#[allow(unused_imports)]
+use std::{
+    path::PathBuf,
+    time::Duration,
+};
+#[allow(unused_imports)]
+use crate::helpers::{
+    errors::CapoValueError,
+    values::{
+        parse_bytesize,
+        parse_duration,
+    },
+};
+#[allow(unused_imports)]
+use super::load;
+
+#[test]
+fn test_get_as() {
+    let config = load("test", &["src/lib/tests"]).unwrap();
+
+    assert_eq!(config.get_as::<u8, _>(&"section3.integer.hundred"),
+        Ok(Some(100)));
+    assert_eq!(config.get_as::<String, _>(&"section1.database.user"),
+        Ok(Some(String::from("user"))));
+    assert_eq!(config.get_as::<u8, _>(&"section3.integer.missing"), Ok(None));
+}
+
+#[test]
+fn test_get_as_invalid() {
+    let config = load("test", &["src/lib/tests/settings"]).unwrap();
+
+    match config.get_as::<u16, _>(&"server.port") {
+        Err(CapoValueError::Invalid { key, value, location, type_name, .. })
+            => {
+            assert_eq!(key, "server.port");
+            assert_eq!(value, "eighty");
+            assert_eq!(location, Some(String::from(
+                "src/lib/tests/settings/test.properties")));
+            assert_eq!(type_name, "u16");
+        },
+        other => panic!("expected an invalid value, got {:?}", other),
+    };
+
+    // With every layer named base.properties, the error names the directory
+    let layered = load("base", &["src/lib/tests/profiles/one",
+        "src/lib/tests/profiles/two"]).unwrap();
+    match layered.get_as::<bool, _>(&"archive.port") {
+        Err(CapoValueError::Invalid { location, .. }) =>
+            assert_eq!(location, Some(String::from(
+                "src/lib/tests/profiles/two/base.properties"))),
+        other => panic!("expected an invalid value, got {:?}", other),
+    };
+
+    // The old getters can't tell a malformed value from a missing one
+    assert_eq!(config.get_u16(&"server.port"), None);
+}
+
+#[test]
+fn test_get_or_and_require() {
+    let config = load("test", &["src/lib/tests/settings"]).unwrap();
+
+    assert_eq!(config.get_or(&"server.timeout", 10u32), Ok(30));
+    assert_eq!(config.get_or(&"server.retries", 3u32), Ok(3));
+    assert!(config.get_or(&"server.port", 80u16).is_err());
+
+    assert_eq!(config.require::<String, _>(&"server.host"),
+        Ok(String::from("localhost")));
+    assert_eq!(config.require::<u32, _>(&"server.retries"),
+        Err(CapoValueError::Missing { key: String::from("server.retries") }));
+}
+
+#[test]
+fn test_get_bool() {
+    let config = load("test", &["src/lib/tests/values"]).unwrap();
+
+    // The old getter and the typed ones read the same spellings
+    for (key, value) in [("archive.compress", true), ("archive.verify", false),
+        ("archive.encrypt", true)] {
+        assert_eq!(config.get_bool(&key), Some(value));
+        assert_eq!(config.get_as::<bool, _>(&key), Ok(Some(value)));
+        assert_eq!(config.require::<bool, _>(&key), Ok(value));
+    }
+
+    assert_eq!(config.get_bool(&"archive.sign"), None);
+    assert!(config.get_as::<bool, _>(&"archive.sign").is_err());
+    assert_eq!(config.get_list::<bool, _>(&"archive.compress"),
+        Ok(Some(vec![true])));
+}
+
+#[test]
+fn test_get_duration() {
+    let config = load("test", &["src/lib/tests/values"]).unwrap();
+
+    assert_eq!(config.get_duration(&"archive.timeout"),
+        Ok(Some(Duration::from_secs(90 * 60))));
+    assert_eq!(config.get_duration(&"archive.retry"),
+        Ok(Some(Duration::from_millis(500))));
+    assert_eq!(config.get_duration(&"archive.poll"),
+        Ok(Some(Duration::from_secs(45))));
+    assert_eq!(config.get_duration(&"archive.missing"), Ok(None));
+
+    match config.get_duration(&"archive.deadline") {
+        Err(CapoValueError::Invalid { location, type_name, .. }) => {
+            assert_eq!(location, Some(String::from(
+                "src/lib/tests/values/test.properties")));
+            assert_eq!(type_name, "duration");
+        },
+        other => panic!("expected an invalid duration, got {:?}", other),
+    };
+}
+
+#[test]
+fn test_parse_duration() {
+    // Counts are u64, so they can be larger than a u32
+    assert_eq!(parse_duration("5000000000ms"),
+        Ok(Duration::from_millis(5_000_000_000)));
+    assert_eq!(parse_duration("5000000000s"),
+        Ok(Duration::from_secs(5_000_000_000)));
+    assert_eq!(parse_duration("1m500ms"), Ok(Duration::from_millis(60_500)));
+    assert!(parse_duration("18446744073709551615w").is_err());
+}
+
+#[test]
+fn test_get_bytesize() {
+    let config = load("test", &["src/lib/tests/values"]).unwrap();
+
+    assert_eq!(config.get_bytesize(&"archive.cache"), Ok(Some(512 << 20)));
+    assert_eq!(config.get_bytesize(&"archive.spill"), Ok(Some(2 << 30)));
+    assert_eq!(config.get_bytesize(&"archive.block"), Ok(Some(4000)));
+    assert!(config.get_bytesize(&"archive.quota").is_err());
+}
+
+#[test]
+fn test_parse_bytesize() {
+    // Every u64 is exact, which a float can't do above 2^53
+    assert_eq!(parse_bytesize("9007199254740993"), Ok(9007199254740993));
+    assert_eq!(parse_bytesize("18446744073709551615"), Ok(u64::MAX));
+    assert!(parse_bytesize("18446744073709551616").is_err());
+    assert!(parse_bytesize("16777216T").is_err());
+
+    assert_eq!(parse_bytesize("1.5KiB"), Ok(1536));
+    assert_eq!(parse_bytesize(".5k"), Ok(512));
+    assert_eq!(parse_bytesize("2.000 MB"), Ok(2_000_000));
+    assert_eq!(parse_bytesize("0.001kb"), Ok(1));
+    assert!(parse_bytesize("1.5").is_err());
+    assert!(parse_bytesize("0.0001kb").is_err());
+
+    assert!(parse_bytesize("").is_err());
+    assert!(parse_bytesize("G").is_err());
+    assert!(parse_bytesize("1.2.3M").is_err());
+    assert!(parse_bytesize("12 parsecs").is_err());
+}
+
+#[test]
+fn test_get_path_and_url() {
+    let config = load("test", &["src/lib/tests/values"]).unwrap();
+    let home = dirs::home_dir().unwrap();
+
+    assert_eq!(config.get_path(&"archive.root"),
+        Ok(Some(home.join("archive"))));
+    assert_eq!(config.get_path(&"archive.scratch"),
+        Ok(Some(PathBuf::from("/tmp/scratch"))));
+
+    let url = config.get_url(&"archive.url").unwrap().unwrap();
+    assert_eq!(url.host_str(), Some("archive.example.org"));
+    assert_eq!(url.port(), Some(8443));
+
+    match config.get_url(&"archive.mirror") {
+        Err(CapoValueError::Invalid { key, type_name, .. }) => {
+            assert_eq!(key, "archive.mirror");
+            assert_eq!(type_name, "URL");
+        },
+        other => panic!("expected an invalid URL, got {:?}", other),
+    };
+}
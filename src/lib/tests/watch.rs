@@ -0,0 +1,115 @@
+#[allow(unused_imports)]
+use std::{
+    fs,
+    sync::{
+        mpsc,
+        Arc,
+    },
+    time::Duration,
+};
+#[allow(unused_imports)]
+use crate::config::watch::CapoWatcher;
+#[allow(unused_imports)]
+use super::load;
+#[allow(unused_imports)]
+use super::common::{
+    scratch_dir,
+    write_file,
+};
+
+#[test]
+fn test_watch_key_changes() {
+    let dir = scratch_dir("watch");
+    write_file(&dir.join("test.properties"),
+        "section1.user = user\nsection1.password = password\n", 1000);
+
+    let config = load("test", &[&dir]).unwrap();
+    let watcher = CapoWatcher::new(config).unwrap();
+
+    let (diff_sender, diffs) = mpsc::channel();
+    watcher.on_change(move |diff| diff_sender.send(diff.clone()).unwrap());
+
+    let (key_sender, keys) = mpsc::channel();
+    watcher.on_key_change(&"section1.user",
+        move |change| key_sender.send(change.clone()).unwrap());
+
+    // Replace the file in one step so the watcher never sees it half written
+    write_file(&dir.join("test.properties.new"),
+        "section1.user = admin\nsection1.host = localhost\n", 2000);
+    fs::rename(dir.join("test.properties.new"), dir.join("test.properties"))
+        .unwrap();
+
+    let diff = diffs.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(diff.added[0].key, "section1.host");
+    assert_eq!(diff.removed[0].key, "section1.password");
+
+    let change = keys.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(change.old_value.as_deref(), Some("user"));
+    assert_eq!(change.new_value.as_deref(), Some("admin"));
+    assert_eq!(change.new_location.as_deref(), Some("test.properties"));
+
+    assert_eq!(watcher.config().get(&"section1.user").unwrap(), "admin");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_watch_callbacks_register_callbacks() {
+    let dir = scratch_dir("watch_register");
+    write_file(&dir.join("test.properties"), "section1.user = user\n", 1000);
+
+    let config = load("test", &[&dir]).unwrap();
+    let watcher = Arc::new(CapoWatcher::new(config).unwrap());
+
+    // The first change registers a callback for the second one
+    let (key_sender, keys) = mpsc::channel();
+    let (registered_sender, registered) = mpsc::channel();
+    let registering = Arc::downgrade(&watcher);
+    watcher.on_change(move |_| {
+        let watcher = match registering.upgrade() {
+            Some(w) => w,
+            None => return,
+        };
+        let key_sender = key_sender.clone();
+        watcher.on_key_change(&"section1.user",
+            move |change| key_sender.send(change.clone()).unwrap());
+        registered_sender.send(()).unwrap();
+    });
+
+    for (user, seconds) in [("admin", 2000), ("root", 3000)] {
+        let new = dir.join("test.properties.new");
+        write_file(&new, &format!("section1.user = {}\n", user), seconds);
+        fs::rename(new, dir.join("test.properties")).unwrap();
+        registered.recv_timeout(Duration::from_secs(10)).unwrap();
+    }
+
+    let change = keys.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(change.new_value.as_deref(), Some("root"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_watch_directories_created_later() {
+    let dir = scratch_dir("watch_later");
+    write_file(&dir.join("test.properties"), "section1.user = user\n", 1000);
+    let later = dir.join("later").join("nested");
+
+    let config = load("test", &[&dir, &later]).unwrap();
+    let watcher = CapoWatcher::new(config).unwrap();
+
+    let (key_sender, keys) = mpsc::channel();
+    watcher.on_key_change(&"section1.host",
+        move |change| key_sender.send(change.clone()).unwrap());
+
+    fs::create_dir_all(&later).unwrap();
+    write_file(&later.join("test.properties.new"),
+        "section1.host = localhost\n", 2000);
+    fs::rename(later.join("test.properties.new"),
+        later.join("test.properties")).unwrap();
+
+    let change = keys.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(change.new_value.as_deref(), Some("localhost"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -1,95 +1,853 @@
-use clap::Parser;
+use std::{
+    collections::HashMap,
+    env,
+    fs,
+    path::PathBuf,
+};
+
+use clap::{
+    error::ErrorKind,
+    Args,
+    CommandFactory,
+    Parser,
+    Subcommand,
+};
 
 use rustcapo::{
+    config::config::CapoHistoryEntry,
+    helpers::defaults,
     CapoConfig,
-    errors,
+    CapoConfigBuilder,
+    CapoError,
+    CapoFileStatus,
+    PropertiesDocument,
+};
+
+mod output;
+mod select;
+mod tests;
+
+use output::{
+    shell_name,
+    shell_quote,
+    Format,
+    Report,
+    Setting,
 };
-// use helpers::errors;
-// use config::config::CapoConfig;
+use select::Selector;
 
-/// Arguments to read CAPO properties
+/// Arguments to read CAPO properties. Without a command the settings are
+/// picked with -A, --settings, --explain or the selectors, as they always
+/// have been
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct CapoArgs {
-    /// Path of directories to search
-    #[arg(long)]
-    path: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path of directories to search; may be repeated to search each in turn,
+    /// and diff compares two
+    #[arg(long, global = true)]
+    path: Vec<String>,
 
     /// Display all settings
     #[arg(long, short='A')]
     all: bool,
 
-    /// quiet mode; only display the value
-    #[arg(long, short)]
-    quiet: bool,
+    #[command(flatten)]
+    output: OutputArgs,
 
     /// one or more settings to query, ignored if -A
     #[arg(long)]
     settings: Vec<String>,
 
-    /// profile name to use, e.g. test, production
-    #[arg(long, short='P')]
-    profile: Option<String>,
+    #[command(flatten)]
+    selection: SelectionArgs,
+
+    /// profile name to use, e.g. test, production, or a comma separated list
+    /// of profiles to layer, e.g. base,test; may be repeated to layer them,
+    /// and diff compares two
+    #[arg(long, short='P', global = true)]
+    profile: Vec<String>,
+
+    /// show every file that set a setting and the value it set, in the order
+    /// the files were read
+    #[arg(long, value_name = "KEY")]
+    explain: Vec<String>,
+
+    /// give a setting a value that overrides every file, e.g.
+    /// --set section1.database.user=alice; may be repeated
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true,
+        value_parser = parse_override)]
+    overrides: Vec<(String, String)>,
 }
 
+/// Arguments for how settings are printed, given before or after a command
+#[derive(Args, Debug)]
+struct OutputArgs {
+    /// quiet mode; only display the value, with the shell and null formats
+    #[arg(long, short, global = true)]
+    quiet: bool,
+
+    /// the format to display settings in, shell if not given
+    #[arg(long, value_enum, global = true)]
+    format: Option<Format>,
+
+    /// print export NAME='value' lines, quoted so they're safe to pass to
+    /// eval "$(rustcapo ...)"
+    #[arg(long, conflicts_with_all = ["format", "quiet"], global = true)]
+    export: bool,
+}
+
+/// Arguments for picking settings by their keys, given before or after a
+/// command
+#[derive(Args, Debug)]
+struct SelectionArgs {
+    /// query every setting whose key starts with a prefix, ignoring case,
+    /// e.g. section2.; may be repeated
+    #[arg(long, global = true)]
+    prefix: Vec<String>,
+
+    /// query every setting whose key matches a glob, ignoring case, e.g.
+    /// 'section*.run'; may be repeated
+    #[arg(long, global = true)]
+    glob: Vec<String>,
+
+    /// query every setting whose key matches a regular expression; may be
+    /// repeated
+    #[arg(long, global = true)]
+    regex: Vec<String>,
+
+    /// remove the longest --prefix each setting starts with from the name it's
+    /// printed with
+    #[arg(long, requires = "prefix", global = true)]
+    strip_prefix: bool,
+}
+
+/// The commands of the command line utility
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[command(flatten)]
+    Read(ReadCommand),
+
+    /// compare two profiles, like -P test -P production, or two paths, like
+    /// --path A --path B, showing the settings added, removed or changed
+    Diff {
+        /// how to display the differences
+        #[arg(long, value_enum, default_value_t = Report::Table)]
+        report: Report,
+    },
+
+    /// give a setting a value in a properties file, keeping the rest of the
+    /// file as it is
+    Set {
+        /// the setting to change
+        key: String,
+
+        /// the value to give it
+        value: String,
+
+        /// the properties file to edit, or a directory to edit the profile's
+        /// file in; by default the last file read that can be written to
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+
+    /// remove a setting from a properties file, keeping the rest of the file
+    /// as it is
+    Unset {
+        /// the setting to remove
+        key: String,
+
+        /// the properties file to edit, or a directory to edit the profile's
+        /// file in; by default the last file read that sets it and can be
+        /// written to
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+}
+
+/// The commands that only read the configuration, run once it's loaded
+#[derive(Subcommand, Debug)]
+enum ReadCommand {
+    /// display the value of one or more settings
+    Get {
+        /// the settings to display
+        #[arg(required = true)]
+        keys: Vec<String>,
+
+        /// the value to display for a setting that isn't defined, instead of
+        /// failing
+        #[arg(long)]
+        default: Option<String>,
+    },
+
+    /// display every setting, or the settings the selectors pick
+    List,
+
+    /// display the file and line a setting's value comes from
+    Where {
+        /// the setting to find
+        key: String,
+    },
+
+    /// show every file that set a setting and the value it set, in the order
+    /// the files were read
+    Explain {
+        /// the settings to explain
+        #[arg(required = true)]
+        keys: Vec<String>,
+    },
+
+    /// list the files that were read and the files that were skipped, with
+    /// the reason they were skipped
+    Files,
+
+    /// list the profiles that have files on the path, marking the ones in use
+    Profiles,
+}
+
+impl CapoArgs {
+    /// Parse the command line, checking the options clap can't check when
+    /// some are given before a command and some after it
+    ///
+    /// # Arguments
+    /// * `args` - An iterator over the arguments, starting with the program
+    ///
+    /// # Return
+    /// A Result with the CapoArgs or the clap Error to exit with
+    fn parse_checked<I, T>(args: I) -> Result<Self, clap::Error>
+        where I: IntoIterator<Item = T>, T: Into<std::ffi::OsString> + Clone {
+
+        let args = Self::try_parse_from(args)?;
+        let output = &args.output;
+        if output.export && (output.format.is_some() || output.quiet) {
+            return Err(Self::command().error(ErrorKind::ArgumentConflict,
+                "the argument '--export' cannot be used with '--format' or \
+                '--quiet'"));
+        }
+
+        Ok(args)
+    }
+}
+
+/// The properties files parsed to find the lines values are on, by path, or
+/// None if a file couldn't be parsed
+type Documents = HashMap<PathBuf, Option<PropertiesDocument>>;
+
 /// This function is called when you type `rustcapo`
 fn main() {
-    let mut args = CapoArgs::parse();
+    let args = CapoArgs::parse_checked(env::args_os())
+        .unwrap_or_else(|e| e.exit());
 
-    // This will exit the program with an error code
-    if !args.all && args.settings.is_empty() {
-        errors::OPTION_ERROR.exit_on_error();
+    // Library errors are only mapped to status codes here
+    if let Err(e) = run(args) {
+        eprintln!("ERROR: {}", e);
+        std::process::exit(e.status_code());
+    }
+}
 
+/// Run the command given on the command line, or print the requested CAPO
+/// settings if there isn't one
+///
+/// # Arguments
+/// * `args` - The CapoArgs parsed from the command line
+///
+/// # Return
+/// A Result with nothing or the CapoError that stopped the program
+fn run(args: CapoArgs) -> Result<(), CapoError> {
+    let (profile, path) = (joined_profiles(&args.profile),
+        joined_paths(&args.path));
+    match args.command {
+        Some(Command::Read(command)) => {
+            let config = load(profile, path, args.overrides)?;
+            read(&config, command, &args.output, &args.selection)
+        },
+        // Editing commands load the configuration themselves, if at all
+        Some(Command::Set { key, value, file }) =>
+            set(profile, path, &key, &value, file),
+        Some(Command::Unset { key, file }) => unset(profile, path, &key, file),
+        // A diff loads two configurations
+        Some(Command::Diff { report }) =>
+            diff(&args.profile, &args.path, args.overrides, report),
+        None => run_flags(args),
     }
+}
 
-    // CAPO properties are stored as uppercase, so the arguments must match
-    for setting in &mut args.settings { *setting = setting.to_uppercase(); }
+/// Run a command that only reads the configuration
+///
+/// # Arguments
+/// * `config` - The loaded CapoConfig
+/// * `command` - The ReadCommand given on the command line
+/// * `output` - The OutputArgs to print settings with
+/// * `selection` - The SelectionArgs to pick the settings to list with
+///
+/// # Return
+/// A Result with nothing or the CapoError that stopped the command
+fn read(config: &CapoConfig, command: ReadCommand, output: &OutputArgs,
+    selection: &SelectionArgs) -> Result<(), CapoError> {
+
+    match command {
+        ReadCommand::Get { keys, default } => {
+            let mut settings = Vec::new();
+            for key in keys {
+                let setting = match (setting(config, &key), &default) {
+                    (Ok(s), _) => s,
+                    (Err(_), Some(d)) => Setting {
+                        key,
+                        value: d.clone(),
+                        location: String::from("default"),
+                    },
+                    (Err(e), None) => return Err(e),
+                };
+                settings.push(setting);
+            }
 
-    let config = match CapoConfig::new(args.profile, args.path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
+            print(output, &unique(settings))
         },
-    };
+        ReadCommand::List => {
+            let selector = Selector::new(&selection.prefix, &selection.glob,
+                &selection.regex)?;
+            let keys: Vec<String> = sorted_keys(config).into_iter()
+                .filter(|k| selector.is_empty() || selector.matches(k))
+                .collect();
+
+            let settings = settings(config, keys, &selector,
+                selection.strip_prefix)?;
+            print(output, &settings)
+        },
+        ReadCommand::Where { key } => {
+            let source = last_source(config, &key, &mut Documents::new())
+                .ok_or_else(|| CapoError::SettingMissing(key.clone()))?;
 
+            println!("{}", source);
+            Ok(())
+        },
+        ReadCommand::Explain { keys } => explain(config, &keys),
+        ReadCommand::Files => {
+            for status in config.files() {
+                let profile = status.profile.unwrap_or_default();
+                match status.skipped {
+                    Some(reason) => println!("skipped {:<10} {}: {}", profile,
+                        status.file.display(), reason),
+                    None => println!("read    {:<10} {}", profile,
+                        status.file.display()),
+                };
+            }
+
+            Ok(())
+        },
+        ReadCommand::Profiles => {
+            let active = config.profiles();
+            for profile in config.available_profiles() {
+                match active.contains(&profile) {
+                    true => println!("{} (in use)", profile),
+                    _ => println!("{}", profile),
+                };
+            }
+
+            Ok(())
+        },
+    }
+}
+
+/// Print the settings picked with -A, --settings, --explain or the
+/// selectors
+///
+/// # Arguments
+/// * `args` - The CapoArgs parsed from the command line, without a command
+///
+/// # Return
+/// A Result with nothing or the CapoError that stopped the program
+fn run_flags(args: CapoArgs) -> Result<(), CapoError> {
+    let selection = &args.selection;
+    let selector = Selector::new(&selection.prefix, &selection.glob,
+        &selection.regex)?;
+    if !args.all && args.settings.is_empty() && args.explain.is_empty()
+        && selector.is_empty() {
+
+        return Err(CapoError::OptionMissing);
+    }
+
+    let config = load(joined_profiles(&args.profile),
+        joined_paths(&args.path), args.overrides)?;
+
+    if !args.explain.is_empty() {
+        return explain(&config, &args.explain);
+    }
+
+    // Every setting is printed in the order the keys sort in, after the ones
+    // asked for by name
+    let keys = sorted_keys(&config);
     let op_list: Vec<String> = match args.all {
-        true => config.options.keys().map(|k| k.clone()).collect(),
-        _ => args.settings.clone(),
+        true => keys,
+        _ => {
+            let mut op_list = args.settings.clone();
+            for key in keys.into_iter().filter(|k| selector.matches(k)) {
+                if !op_list.iter().any(|s| s.eq_ignore_ascii_case(&key)) {
+                    op_list.push(key);
+                }
+            }
+            op_list
+        },
     };
 
-    for key in op_list {
-        let key = key.to_uppercase();
-        let (setting, location) = match (config.get(&key),
-            config.get_location(&key)) {
+    let settings = settings(&config, op_list, &selector,
+        selection.strip_prefix)?;
+    print(&args.output, &settings)
+}
 
-            (Some(s), Some(l)) => (s, l),
-            _ => {
-                errors::SETTING_ERROR.exit_on_error();
-                return;
-            }
-        };
+/// Load the configuration the command line describes
+///
+/// # Arguments
+/// * `profile` - An `Option<String>` with the profile from the command line
+/// * `path` - An `Option<String>` with the path from the command line
+/// * `overrides` - A `Vec<(String, String)>` of the settings given with --set
+///
+/// # Return
+/// A Result with the CapoConfig or the CapoError it couldn't be loaded with
+fn load(profile: Option<String>, path: Option<String>,
+    overrides: Vec<(String, String)>) -> Result<CapoConfig, CapoError> {
+
+    let mut builder = builder(profile, path);
+    for (key, value) in overrides {
+        builder = builder.with_override(key, value);
+    }
 
-        let format = match args.quiet {
-            true => format!("{}", setting),
-            _ => format!("{}='{}' # {}",
-                fix_key(&key),
-                setting,
-                location
-            ),
+    builder.build()
+}
+
+/// Describe the configuration the command line asks for
+///
+/// # Arguments
+/// * `profile` - An `Option<String>` with the profile from the command line
+/// * `path` - An `Option<String>` with the path from the command line
+///
+/// # Return
+/// The CapoConfigBuilder for the profile and path
+fn builder(profile: Option<String>, path: Option<String>) -> CapoConfigBuilder {
+    let mut builder = CapoConfig::builder();
+    if let Some(p) = profile {
+        builder = builder.profile(p);
+    }
+
+    if let Some(p) = path {
+        builder = builder.path(env::split_paths(&p).collect());
+    }
+
+    builder
+}
+
+/// Join the profiles given with -P into one list of profiles to layer
+///
+/// # Arguments
+/// * `profiles` - A slice of the profiles from the command line
+///
+/// # Return
+/// An `Option<String>` with the comma separated profiles, or None if there
+/// are none
+fn joined_profiles(profiles: &[String]) -> Option<String> {
+    match profiles.is_empty() {
+        true => None,
+        _ => Some(profiles.join(",")),
+    }
+}
+
+/// Join the paths given with --path into one path
+///
+/// # Arguments
+/// * `paths` - A slice of the paths from the command line
+///
+/// # Return
+/// An `Option<String>` with the directories of every path in order, or None
+/// if there are none
+fn joined_paths(paths: &[String]) -> Option<String> {
+    if paths.is_empty() {
+        return None;
+    }
+
+    // Split paths can always be joined again
+    env::join_paths(paths.iter().flat_map(env::split_paths)).ok()
+        .map(|p| p.to_string_lossy().into_owned())
+}
+
+/// Print how two profiles, or two paths, differ
+///
+/// # Arguments
+/// * `profiles` - A slice of the profiles from the command line
+/// * `paths` - A slice of the paths from the command line
+/// * `overrides` - A `Vec<(String, String)>` of the settings given with --set,
+///   applied to both configurations
+/// * `report` - The Report to print
+///
+/// # Return
+/// A Result with nothing or a CapoError if there aren't two profiles or two
+/// paths, or a configuration couldn't be loaded
+fn diff(profiles: &[String], paths: &[String],
+    overrides: Vec<(String, String)>, report: Report) -> Result<(), CapoError> {
+
+    let (old_name, new_name, old, new) = match (profiles, paths) {
+        ([old, new], [] | [_]) => (old, new,
+            load(Some(old.clone()), joined_paths(paths), overrides.clone())?,
+            load(Some(new.clone()), joined_paths(paths), overrides)?),
+        ([] | [_], [old, new]) => (old, new,
+            load(joined_profiles(profiles), Some(old.clone()),
+                overrides.clone())?,
+            load(joined_profiles(profiles), Some(new.clone()), overrides)?),
+        _ => return Err(CapoError::DiffTargets),
+    };
+
+    // Both sides usually read files with the same names, so each location is
+    // given as the file and line the value comes from
+    let mut diff = old.diff(&new);
+    let mut documents = Documents::new();
+    let changes = diff.added.iter_mut()
+        .chain(diff.removed.iter_mut())
+        .chain(diff.changed.iter_mut());
+    for change in changes {
+        change.old_location = last_source(&old, &change.key, &mut documents)
+            .or(change.old_location.take());
+        change.new_location = last_source(&new, &change.key, &mut documents)
+            .or(change.new_location.take());
+    }
+
+    print!("{}", output::render_diff(report, &diff, old_name, new_name));
+    Ok(())
+}
+
+/// Describe where a setting's value came from
+///
+/// # Arguments
+/// * `config` - The loaded CapoConfig
+/// * `key` - A &str with the setting, in any case
+/// * `documents` - The Documents already parsed, to parse each file once
+///
+/// # Return
+/// An `Option<String>` with the source of the value, or None if the setting
+/// is missing
+fn last_source(config: &CapoConfig, key: &str, documents: &mut Documents)
+    -> Option<String> {
+
+    config.get_history(&key)
+        .and_then(|h| h.last().cloned())
+        .map(|e| source_of(&e, key, documents))
+}
+
+/// Get every key of a configuration
+///
+/// # Arguments
+/// * `config` - The loaded CapoConfig
+///
+/// # Return
+/// A `Vec<String>` of the keys as the files spell them, sorted
+fn sorted_keys(config: &CapoConfig) -> Vec<String> {
+    let mut keys: Vec<String> = config.options.keys().cloned().collect();
+    keys.sort();
+    keys
+}
+
+/// Look up a setting to print
+///
+/// # Arguments
+/// * `config` - The loaded CapoConfig
+/// * `key` - A &str with the setting, in any case
+///
+/// # Return
+/// A Result with the Setting, its key spelled the way the files spell it, or
+/// a CapoError if it's missing
+fn setting(config: &CapoConfig, key: &str) -> Result<Setting, CapoError> {
+    match (config.get_key(&key), config.get(&key), config.get_location(&key)) {
+        (Some(key), Some(value), Some(location)) =>
+            Ok(Setting { key, value, location }),
+        _ => Err(CapoError::SettingMissing(key.to_string())),
+    }
+}
+
+/// Look up the settings to print, naming them without their prefix if asked
+///
+/// # Arguments
+/// * `config` - The loaded CapoConfig
+/// * `keys` - A `Vec<String>` of the settings to look up
+/// * `selector` - The Selector with the prefixes to strip
+/// * `strip_prefix` - A bool that is true to strip the prefixes
+///
+/// # Return
+/// A Result with a `Vec<Setting>` without repeats, or a CapoError if a
+/// setting is missing or stripping leaves two settings with the same name
+fn settings(config: &CapoConfig, keys: Vec<String>, selector: &Selector,
+    strip_prefix: bool) -> Result<Vec<Setting>, CapoError> {
+
+    let mut found = Vec::new();
+    for key in keys {
+        found.push(setting(config, &key)?);
+    }
+
+    let mut settings = unique(found);
+    if !strip_prefix {
+        return Ok(settings);
+    }
+
+    let mut names: HashMap<String, String> = HashMap::new();
+    for setting in &mut settings {
+        let name = selector.strip(&setting.key);
+        if let Some(first) = names.insert(name.clone(), setting.key.clone()) {
+            return Err(CapoError::NameCollision {
+                name,
+                first,
+                second: setting.key.clone(),
+            });
+        }
+
+        setting.key = name;
+    }
+
+    Ok(settings)
+}
+
+/// Drop the settings that were asked for more than once, in any case
+///
+/// # Arguments
+/// * `settings` - A `Vec<Setting>` of the settings that were looked up
+///
+/// # Return
+/// A `Vec<Setting>` with the first of each setting, in order
+fn unique(settings: Vec<Setting>) -> Vec<Setting> {
+    let mut unique: Vec<Setting> = Vec::new();
+    for setting in settings {
+        let key = setting.key.to_uppercase();
+        if !unique.iter().any(|s| s.key.to_uppercase() == key) {
+            unique.push(setting);
+        }
+    }
+
+    unique
+}
+
+/// Print settings in the format the command line asks for
+///
+/// # Arguments
+/// * `output` - The OutputArgs parsed from the command line
+/// * `settings` - A slice of the Settings to print
+///
+/// # Return
+/// A Result with nothing or a CapoError if two settings would be printed as
+/// the same shell variable
+fn print(output: &OutputArgs, settings: &[Setting]) -> Result<(), CapoError> {
+    let format = match output.export {
+        true => Format::Export,
+        _ => output.format.unwrap_or_default(),
+    };
+
+    print!("{}", output::render(format, settings, output.quiet)?);
+    Ok(())
+}
+
+/// Describe where a value came from
+///
+/// # Arguments
+/// * `entry` - The CapoHistoryEntry of the value
+/// * `key` - A &str with the setting the value was given to
+/// * `documents` - The Documents already parsed, to parse each file once
+///
+/// # Return
+/// A String with the file and line of a properties file, the file of another
+/// format, or the location of a value from the environment or --set
+fn source_of(entry: &CapoHistoryEntry, key: &str, documents: &mut Documents)
+    -> String {
+
+    // Values from the environment or --set have no file
+    if entry.file.as_os_str().is_empty() {
+        return entry.location.clone();
+    }
+
+    let line = match entry.file.extension().is_some_and(|e| e == "properties") {
+        true => documents.entry(entry.file.clone())
+            .or_insert_with(|| PropertiesDocument::from_file(&entry.file).ok())
+            .as_ref()
+            .and_then(|d| d.line_of(key)),
+        _ => None,
+    };
+
+    match line {
+        Some(l) => format!("{}:{}", entry.file.display(), l),
+        None => entry.file.display().to_string(),
+    }
+}
+
+/// Print every value the given settings were given, commenting out the ones
+/// that were overridden by a later file
+///
+/// # Arguments
+/// * `config` - The loaded CapoConfig
+/// * `keys` - A slice of the setting names to explain
+///
+/// # Return
+/// A Result with nothing or a CapoError if a setting is missing
+fn explain(config: &CapoConfig, keys: &[String]) -> Result<(), CapoError> {
+    let mut documents = Documents::new();
+    for key in keys {
+        let (key, history) = match (config.get_key(key),
+            config.get_history(key)) {
+
+            (Some(k), Some(h)) => (k, h),
+            _ => return Err(CapoError::SettingMissing(key.clone())),
         };
 
-        println!("{}", format);
+        let last = history.len() - 1;
+        for (i, entry) in history.iter().enumerate() {
+            let overridden = match i == last {
+                true => "",
+                _ => "# ",
+            };
+
+            println!("{}{}={} # {}",
+                overridden,
+                shell_name(&key),
+                shell_quote(&entry.value),
+                source_of(entry, &key, &mut documents)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Give a setting a value in a properties file
+///
+/// # Arguments
+/// * `profile` - An `Option<String>` with the profile from the command line
+/// * `path` - An `Option<String>` with the path from the command line
+/// * `key` - A &str with the setting to change
+/// * `value` - A &str with the value to give it
+/// * `file` - An `Option<PathBuf>` with the file or directory to edit
+///
+/// # Return
+/// A Result with nothing or a CapoError if no file could be edited
+fn set(profile: Option<String>, path: Option<String>, key: &str, value: &str,
+    file: Option<PathBuf>) -> Result<(), CapoError> {
+
+    let target = match file {
+        Some(f) => chosen_file(profile, f)?,
+        None => {
+            let files = builder(profile.clone(), path).files()?;
+            writable_files(&files).pop().ok_or_else(||
+                CapoError::NoWritableFile {
+                    profile: profile.or_else(|| env::var(defaults::CAPO_ENV_VAR)
+                        .ok()).unwrap_or_default(),
+                })?
+        },
+    };
+
+    // The file is created if it doesn't exist yet
+    let mut document = match target.exists() {
+        true => PropertiesDocument::from_file(&target)?,
+        _ => PropertiesDocument::new(),
+    };
+    document.insert(key, value);
+    document.save(&target)?;
+
+    eprintln!("{} set in {}", key, target.display());
+    Ok(())
+}
+
+/// Remove a setting from a properties file
+///
+/// # Arguments
+/// * `profile` - An `Option<String>` with the profile from the command line
+/// * `path` - An `Option<String>` with the path from the command line
+/// * `key` - A &str with the setting to remove
+/// * `file` - An `Option<PathBuf>` with the file or directory to edit
+///
+/// # Return
+/// A Result with nothing or a CapoError if the setting isn't in a file that
+/// can be edited
+fn unset(profile: Option<String>, path: Option<String>, key: &str,
+    file: Option<PathBuf>) -> Result<(), CapoError> {
+
+    let files = match file {
+        Some(f) => vec![chosen_file(profile, f)?],
+        None => writable_files(&builder(profile, path).files()?),
     };
+
+    for target in files.iter().rev() {
+        let mut document = PropertiesDocument::from_file(target)?;
+        if document.remove(key).is_some() {
+            document.save(target)?;
+
+            eprintln!("{} removed from {}", key, target.display());
+            return Ok(());
+        }
+    }
+
+    Err(CapoError::SettingMissing(key.to_string()))
+}
+
+/// Get the properties files that were read and can be written to
+///
+/// # Arguments
+/// * `statuses` - A slice of the CapoFileStatuses of the files that were
+///   looked for
+///
+/// # Return
+/// A `Vec<PathBuf>` of the files, the last one having the highest priority
+fn writable_files(statuses: &[CapoFileStatus]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for status in statuses.iter().filter(|s| s.skipped.is_none()) {
+        let path = &status.file;
+        let is_properties = path.extension()
+            .is_some_and(|e| e == "properties");
+        let writable = fs::metadata(path)
+            .is_ok_and(|m| !m.permissions().readonly());
+
+        if is_properties && writable && !files.contains(path) {
+            files.push(path.clone());
+        }
+    }
+
+    files
 }
 
-/// Clean up the option name for use in shell scripts
+/// Find the properties file to edit from the --file argument
 ///
 /// # Arguments
-/// * `key` - A ToString with the option name
+/// * `profile` - An `Option<String>` with the profile from the command line
+/// * `file` - A PathBuf with the file, or a directory to find the profile's
+///   file in
 ///
 /// # Return
-/// A String with the reformatted option name
-fn fix_key<T: ToString>(key: &T) -> String {
-    key.to_string().replace(".", "_").replace("-", "_")
+/// A Result with the PathBuf of the file or a CapoError if a directory was
+/// given without a profile
+fn chosen_file(profile: Option<String>, file: PathBuf)
+    -> Result<PathBuf, CapoError> {
+
+    if !file.is_dir() {
+        return Ok(file);
+    }
+
+    // The last of several layered profiles has the highest priority
+    let profile = profile.or_else(|| env::var(defaults::CAPO_ENV_VAR).ok())
+        .and_then(|p| p.split(',')
+            .map(|p| p.trim().to_string())
+            .rfind(|p| !p.is_empty()))
+        .ok_or(CapoError::ProfileMissing {
+            env_var: Some(defaults::CAPO_ENV_VAR.to_string()),
+        })?;
+
+    Ok(file.join(format!("{}.properties", profile)))
+}
+
+/// Split a --set argument into the setting and its value
+///
+/// # Arguments
+/// * `arg` - A &str with the argument, like "key=value"
+///
+/// # Return
+/// A Result with the key and value or a String saying why the argument is
+/// invalid
+fn parse_override(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() =>
+            Ok((key.trim().to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{}'", arg)),
+    }
 }
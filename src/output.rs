@@ -0,0 +1,421 @@
+//! This file contains the formats the command line utility can print settings
+//! in.
+//!
+//! Strings are escaped by hand so the formats don't need the optional serde
+//! features of the library.
+//!
+//! The shell, env and export formats name each setting with a variable made
+//! from its key: every character that isn't an ASCII letter, digit or
+//! underscore becomes an underscore, a name starting with a digit gets a
+//! leading underscore, and the case is kept. `section1.database.user` is
+//! `section1_database_user` and `2nd-host` is `_2nd_host`. Keys that would
+//! share a variable, like `a.b` and `a-b`, are an error.
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+
+use rustcapo::{
+    CapoError,
+    CapoKeyChange,
+    ConfigDiff,
+    PropertiesDocument,
+};
+
+/// The formats settings can be printed in
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// KEY='value' # file, one setting per line
+    #[default]
+    Shell,
+
+    /// an object with the value and location of each setting
+    Json,
+
+    /// a mapping with the value and location of each setting
+    Yaml,
+
+    /// a table with the value and location of each setting
+    Toml,
+
+    /// a properties file that CAPO reads back as the same settings
+    Properties,
+
+    /// KEY="value" lines for env files
+    Env,
+
+    /// key=value records ending in NUL bytes, for `xargs -0`
+    Null,
+
+    /// export NAME='value' lines for `eval "$(rustcapo ...)"`, chosen with
+    /// --export
+    #[value(skip)]
+    Export,
+}
+
+/// The reports the differences between two configurations can be printed as
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Report {
+    /// a column with each side's value and location, one setting per line
+    #[default]
+    Table,
+
+    /// -key=value # file and +key=value # file lines, like diff -u
+    Unified,
+
+    /// an object with the added, removed and changed settings
+    Json,
+}
+
+/// A setting to print
+pub struct Setting {
+    pub key: String,
+    pub value: String,
+    pub location: String,
+}
+
+/// Format settings for printing
+///
+/// # Arguments
+/// * `format` - The Format to print in
+/// * `settings` - A slice of the Settings in the order they're printed
+/// * `quiet` - A bool that is true to print only the values, for the shell
+///   and null formats
+///
+/// # Return
+/// A Result with a String of the formatted settings, ending in a newline
+/// unless the format is null, or a CapoError if two settings would print as
+/// the same shell variable or key, or a null record would have a NUL byte
+pub fn render(format: Format, settings: &[Setting], quiet: bool)
+    -> Result<String, CapoError> {
+
+    let named = matches!((format, quiet),
+        (Format::Shell, false) | (Format::Env, _) | (Format::Export, _));
+    if named {
+        check_names(settings)?;
+    }
+
+    let keyed = matches!(format,
+        Format::Json | Format::Yaml | Format::Toml | Format::Properties);
+    if keyed {
+        check_keys(settings)?;
+    }
+
+    // A NUL byte would end a record early
+    if format == Format::Null {
+        let nul = settings.iter()
+            .find(|s| s.value.contains('\0')
+                || (!quiet && s.key.contains('\0')));
+        if let Some(setting) = nul {
+            return Err(CapoError::NulByte(setting.key.clone()));
+        }
+    }
+
+    let rendered = match (format, quiet) {
+        (Format::Shell, true) => lines(settings.iter()
+            .map(|s| s.value.clone())),
+        (Format::Shell, _) => lines(settings.iter()
+            .map(|s| format!("{}={} # {}", shell_name(&s.key),
+                shell_quote(&s.value), s.location.replace('\n', " ")))),
+        (Format::Export, _) => lines(settings.iter()
+            .map(|s| format!("export {}={}", shell_name(&s.key),
+                shell_quote(&s.value)))),
+        (Format::Json, _) => json(settings),
+        (Format::Yaml, _) => lines(settings.iter()
+            .map(|s| format!("{}:\n  value: {}\n  location: {}",
+                quote(&s.key), quote(&s.value), quote(&s.location)))),
+        (Format::Toml, _) => lines(settings.iter()
+            .map(|s| format!("{} = {{ value = {}, location = {} }}",
+                quote(&s.key), quote(&s.value), quote(&s.location)))),
+        (Format::Properties, _) => {
+            let mut document = PropertiesDocument::new();
+            for setting in settings {
+                document.insert(&setting.key, &setting.value);
+            }
+            document.to_string()
+        },
+        (Format::Env, _) => lines(settings.iter()
+            .map(|s| format!("{}={}", shell_name(&s.key),
+                env_quote(&s.value)))),
+        (Format::Null, true) => settings.iter()
+            .map(|s| format!("{}\0", s.value))
+            .collect(),
+        (Format::Null, _) => settings.iter()
+            .map(|s| format!("{}={}\0", s.key, s.value))
+            .collect(),
+    };
+
+    Ok(rendered)
+}
+
+/// Format the differences between two configurations for printing
+///
+/// # Arguments
+/// * `report` - The Report to print
+/// * `diff` - The ConfigDiff of the two configurations
+/// * `old` - A &str naming the configuration compared from, like its profile
+/// * `new` - A &str naming the configuration compared with
+///
+/// # Return
+/// A String with the report, ending in a newline
+pub fn render_diff(report: Report, diff: &ConfigDiff, old: &str, new: &str)
+    -> String {
+
+    // The settings are printed in the order their keys sort in, whatever the
+    // change
+    let mut changes: Vec<&CapoKeyChange> = diff.changes().collect();
+    changes.sort_by_key(|c| c.key.to_uppercase());
+
+    match report {
+        Report::Table => table(&changes, old, new),
+        Report::Unified => {
+            let mut lines = vec![format!("--- {}", old), format!("+++ {}", new)];
+            for change in changes {
+                let sides = [
+                    ('-', &change.old_value, &change.old_location),
+                    ('+', &change.new_value, &change.new_location),
+                ];
+                for (sign, value, location) in sides {
+                    if let Some(v) = value {
+                        lines.push(format!("{}{}={} # {}", sign, change.key,
+                            v.replace('\n', "\\n"),
+                            location.as_deref().unwrap_or_default()));
+                    }
+                }
+            }
+            self::lines(lines.into_iter())
+        },
+        Report::Json => {
+            let list = |changes: &[CapoKeyChange]| match changes.is_empty() {
+                true => String::from("[]"),
+                _ => format!("[\n{}\n  ]", changes.iter()
+                    .map(|c| format!("    {}", json_change(c)))
+                    .collect::<Vec<String>>()
+                    .join(",\n")),
+            };
+
+            format!("{{\n  \"old\": {},\n  \"new\": {},\n  \"added\": {},\n  \
+                \"removed\": {},\n  \"changed\": {}\n}}\n", quote(old),
+                quote(new), list(&diff.added), list(&diff.removed),
+                list(&diff.changed))
+        },
+    }
+}
+
+/// Format the differences between two configurations as a table
+///
+/// # Arguments
+/// * `changes` - A slice of the CapoKeyChanges in the order they're printed
+/// * `old` - A &str naming the configuration compared from
+/// * `new` - A &str naming the configuration compared with
+///
+/// # Return
+/// A String with a header and a row for each setting, marked + if it was
+/// added, - if it was removed and ~ if it was changed
+fn table(changes: &[&CapoKeyChange], old: &str, new: &str) -> String {
+    let side = |value: &Option<String>, location: &Option<String>|
+        match (value, location) {
+            (Some(v), Some(l)) => format!("{} ({})", v.replace('\n', "\\n"),
+                l),
+            (Some(v), None) => v.replace('\n', "\\n"),
+            _ => String::from("-"),
+        };
+
+    let mut rows = vec![(' ', String::from("KEY"), old.to_string(),
+        new.to_string())];
+    for change in changes {
+        let mark = match (&change.old_value, &change.new_value) {
+            (None, _) => '+',
+            (_, None) => '-',
+            _ => '~',
+        };
+        rows.push((mark, change.key.clone(),
+            side(&change.old_value, &change.old_location),
+            side(&change.new_value, &change.new_location)));
+    }
+
+    let key_width = rows.iter().map(|r| r.1.chars().count()).max()
+        .unwrap_or_default();
+    let old_width = rows.iter().map(|r| r.2.chars().count()).max()
+        .unwrap_or_default();
+
+    lines(rows.into_iter()
+        .map(|(mark, key, old, new)| format!("{} {:<key_width$}  \
+            {:<old_width$}  {}", mark, key, old, new)
+            .trim_end()
+            .to_string()))
+}
+
+/// Format a change as a single line JSON object
+fn json_change(change: &CapoKeyChange) -> String {
+    let value = |v: &Option<String>| match v {
+        Some(v) => quote(v),
+        None => String::from("null"),
+    };
+
+    format!("{{\"key\": {}, \"old_value\": {}, \"new_value\": {}, \
+        \"old_location\": {}, \"new_location\": {}}}", quote(&change.key),
+        value(&change.old_value), value(&change.new_value),
+        value(&change.old_location), value(&change.new_location))
+}
+
+/// Check that no two settings would print as the same shell variable
+///
+/// # Arguments
+/// * `settings` - A slice of the Settings to print
+///
+/// # Return
+/// A Result with nothing or a CapoError naming the variable and both keys
+fn check_names(settings: &[Setting]) -> Result<(), CapoError> {
+    let mut names: HashMap<String, &str> = HashMap::new();
+    for setting in settings {
+        match names.insert(shell_name(&setting.key), &setting.key) {
+            Some(first) if first != setting.key =>
+                return Err(CapoError::NameCollision {
+                    name: shell_name(&setting.key),
+                    first: first.to_string(),
+                    second: setting.key.clone(),
+                }),
+            _ => {},
+        };
+    }
+
+    Ok(())
+}
+
+/// Check that no two settings have the same key in any case, which a document
+/// can't hold twice and CAPO would read back as one setting
+///
+/// # Arguments
+/// * `settings` - A slice of the Settings to print
+///
+/// # Return
+/// A Result with nothing or a CapoError naming both keys
+fn check_keys(settings: &[Setting]) -> Result<(), CapoError> {
+    let mut keys: HashMap<String, &str> = HashMap::new();
+    for setting in settings {
+        if let Some(first) = keys.insert(setting.key.to_uppercase(),
+            &setting.key) {
+
+            return Err(CapoError::NameCollision {
+                name: setting.key.clone(),
+                first: first.to_string(),
+                second: setting.key.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Join lines, ending each in a newline
+fn lines(lines: impl Iterator<Item = String>) -> String {
+    lines.map(|l| l + "\n").collect()
+}
+
+/// Format settings as a JSON object keyed by the settings
+///
+/// # Arguments
+/// * `settings` - A slice of the Settings in the order they're printed
+///
+/// # Return
+/// A String with the pretty printed object
+fn json(settings: &[Setting]) -> String {
+    let members: Vec<String> = settings.iter()
+        .map(|s| format!(
+            "  {}: {{\n    \"value\": {},\n    \"location\": {}\n  }}",
+            quote(&s.key), quote(&s.value), quote(&s.location)))
+        .collect();
+
+    match members.is_empty() {
+        true => String::from("{}\n"),
+        _ => format!("{{\n{}\n}}\n", members.join(",\n")),
+    }
+}
+
+/// Quote a string the way JSON writes it, which YAML double quoted strings
+/// and TOML basic strings read the same way
+///
+/// # Arguments
+/// * `text` - A &str with the string
+///
+/// # Return
+/// A String with the quoted and escaped string
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}",
+                c as u32)),
+            c => quoted.push(c),
+        };
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// Quote a value for an env file, escaping what a double quoted shell string
+/// would expand
+///
+/// # Arguments
+/// * `text` - A &str with the value
+///
+/// # Return
+/// A String with the quoted and escaped value
+fn env_quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' | '\\' | '$' | '`' => {
+                quoted.push('\\');
+                quoted.push(c);
+            },
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        };
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// Quote a value so a POSIX shell reads it back unchanged. The value is put
+/// in single quotes, inside which nothing is expanded, and each single quote
+/// in it is written as `'\''`
+///
+/// # Arguments
+/// * `text` - A &str with the value
+///
+/// # Return
+/// A String with the quoted value
+pub fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Get the shell variable a setting is printed as
+///
+/// # Arguments
+/// * `key` - A &str with the setting's key
+///
+/// # Return
+/// A String with the variable name, made as the module documentation
+/// describes
+pub fn shell_name(key: &str) -> String {
+    let name: String = key.chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            _ => '_',
+        })
+        .collect();
+
+    match name.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("_{}", name),
+        Some(_) => name,
+        None => String::from("_"),
+    }
+}
@@ -0,0 +1,119 @@
+//! This file contains the selection of settings by key prefix, glob pattern
+//! or regular expression.
+//!
+//! A setting is selected if any prefix, glob or regex matches its key.
+//! Prefixes and globs ignore case like every other lookup, while a regex
+//! matches the key as the files spell it unless it starts with `(?i)`.
+use glob::{
+    MatchOptions,
+    Pattern,
+};
+use regex::Regex;
+
+use rustcapo::{
+    helpers::keys::strip_prefix_ignoring_case,
+    CapoError,
+};
+
+/// How globs match keys, where `*` may span dots
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// The prefixes, globs and regexes settings are selected with
+pub struct Selector {
+    prefixes: Vec<String>,
+    globs: Vec<Pattern>,
+    regexes: Vec<Regex>,
+}
+
+impl Selector {
+    /// Compile the patterns settings are selected with
+    ///
+    /// # Arguments
+    /// * `prefixes` - A slice of the key prefixes
+    /// * `globs` - A slice of the glob patterns
+    /// * `regexes` - A slice of the regular expressions
+    ///
+    /// # Return
+    /// A Result with the Selector or a CapoError if a pattern isn't valid
+    pub fn new(prefixes: &[String], globs: &[String], regexes: &[String])
+        -> Result<Self, CapoError> {
+
+        let invalid = |pattern: &String, reason: String|
+            CapoError::InvalidPattern {
+                pattern: pattern.clone(),
+                reason,
+            };
+
+        let mut compiled_globs = Vec::new();
+        for glob in globs {
+            compiled_globs.push(Pattern::new(glob)
+                .map_err(|e| invalid(glob, e.to_string()))?);
+        }
+
+        let mut compiled_regexes = Vec::new();
+        for regex in regexes {
+            compiled_regexes.push(Regex::new(regex)
+                .map_err(|e| invalid(regex, e.to_string()))?);
+        }
+
+        Ok(Self {
+            prefixes: prefixes.to_vec(),
+            globs: compiled_globs,
+            regexes: compiled_regexes,
+        })
+    }
+
+    /// Check whether any patterns were given
+    ///
+    /// # Return
+    /// A bool that is true if there are no prefixes, globs or regexes
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && self.globs.is_empty()
+            && self.regexes.is_empty()
+    }
+
+    /// Check whether a key is selected
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the key as the files spell it
+    ///
+    /// # Return
+    /// A bool that is true if any prefix, glob or regex matches the key
+    pub fn matches(&self, key: &str) -> bool {
+        self.after_prefix(key).is_some()
+            || self.globs.iter().any(|g| g.matches_with(key, GLOB_OPTIONS))
+            || self.regexes.iter().any(|r| r.is_match(key))
+    }
+
+    /// Remove the longest prefix a key starts with, along with the dots after
+    /// it
+    ///
+    /// # Arguments
+    /// * `key` - A &str with the key as the files spell it
+    ///
+    /// # Return
+    /// A String with the rest of the key, or the whole key if no prefix
+    /// matches it or nothing would be left
+    pub fn strip(&self, key: &str) -> String {
+        let rest = self.after_prefix(key)
+            .map(|r| r.trim_start_matches('.'))
+            .unwrap_or(key);
+
+        match rest.is_empty() {
+            true => key.to_string(),
+            _ => rest.to_string(),
+        }
+    }
+
+    /// Find what's left of a key after the longest prefix it starts with,
+    /// ignoring case
+    fn after_prefix<'k>(&self, key: &'k str) -> Option<&'k str> {
+        self.prefixes.iter()
+            .filter_map(|p| strip_prefix_ignoring_case(key, p))
+            .min_by_key(|rest| rest.len())
+    }
+}
@@ -0,0 +1,53 @@
+#[allow(unused_imports)]
+use clap::Parser;
+#[allow(unused_imports)]
+use crate::{
+    output::Format,
+    CapoArgs,
+    Command,
+    ReadCommand,
+};
+
+#[allow(dead_code)]
+fn parse(args: &[&str]) -> CapoArgs {
+    CapoArgs::parse_checked([&["rustcapo"], args].concat()).unwrap()
+}
+
+#[test]
+fn test_output_args_before_or_after_command() {
+    for args in [["-P", "test", "--export", "list"],
+        ["-P", "test", "list", "--export"]] {
+
+        let args = parse(&args);
+        assert!(matches!(args.command, Some(Command::Read(ReadCommand::List))));
+        assert!(args.output.export);
+    }
+
+    for args in [["--format", "json", "get", "K"],
+        ["get", "--format", "json", "K"]] {
+
+        let args = parse(&args);
+        assert!(matches!(args.command, Some(Command::Read(ReadCommand::Get { .. }))));
+        assert_eq!(args.output.format, Some(Format::Json));
+    }
+
+    assert!(parse(&["-q", "get", "K"]).output.quiet);
+    // --export conflicts with --format and --quiet wherever they're given
+    let conflicts: [&[&str]; 3] = [
+        &["--export", "get", "--format", "json", "K"],
+        &["--format", "json", "get", "--export", "K"],
+        &["-q", "list", "--export"],
+    ];
+    for args in conflicts {
+        assert!(CapoArgs::parse_checked([&["rustcapo"], args].concat())
+            .is_err());
+    }
+}
+
+#[test]
+fn test_selection_args_before_or_after_command() {
+    for args in [["--prefix", "a.", "list"], ["list", "--prefix", "a."]] {
+        let args = parse(&args);
+        assert_eq!(args.selection.prefix, vec![String::from("a.")]);
+    }
+}
@@ -0,0 +1,77 @@
+#[allow(unused_imports)]
+use std::{
+    env,
+    fs,
+    path::PathBuf,
+    process,
+};
+#[allow(unused_imports)]
+use rustcapo::CapoError;
+#[allow(unused_imports)]
+use crate::{
+    set,
+    unset,
+};
+
+/// A profile no one has files for in their ~/.capo
+#[allow(dead_code)]
+const PROFILE: &str = "rustcapo-edit-test";
+
+/// Create a directory holding the profile's file with the given contents
+#[allow(dead_code)]
+fn scratch_file(name: &str, contents: &str) -> (String, PathBuf) {
+    let dir = env::temp_dir()
+        .join(format!("rustcapo-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let file = dir.join(format!("{}.properties", PROFILE));
+    fs::write(&file, contents).unwrap();
+    (dir.to_string_lossy().into_owned(), file)
+}
+
+#[allow(dead_code)]
+fn profile() -> Option<String> {
+    Some(String::from(PROFILE))
+}
+
+#[test]
+fn test_set_repairs_unresolvable_value() {
+    let (dir, file) = scratch_file("edit-set",
+        "# kept\na = ${missing}\nb = 1\n");
+
+    set(profile(), Some(dir.clone()), "a", "fixed", None).unwrap();
+    set(profile(), Some(dir), "c", "new", None).unwrap();
+    assert_eq!(fs::read_to_string(file).unwrap(),
+        "# kept\na = fixed\nb = 1\nc = new\n");
+}
+
+#[test]
+fn test_set_after_backslash_at_end_of_file() {
+    let (dir, file) = scratch_file("edit-backslash", "a = 1\r\nb = two\\");
+
+    set(profile(), Some(dir), "c", "3", None).unwrap();
+    assert_eq!(fs::read_to_string(file).unwrap(),
+        "a = 1\r\nb = two\r\nc = 3");
+}
+
+#[test]
+fn test_set_in_directory() {
+    let (dir, file) = scratch_file("edit-dir", "");
+    fs::remove_file(&file).unwrap();
+
+    // The profile's file is created in the directory
+    set(profile(), None, "a", "1", Some(PathBuf::from(&dir))).unwrap();
+    assert_eq!(fs::read_to_string(file).unwrap(), "a = 1\n");
+}
+
+#[test]
+fn test_unset() {
+    let (dir, file) = scratch_file("edit-unset",
+        "a = ${missing}\nb = 1\n");
+
+    unset(profile(), Some(dir.clone()), "B", None).unwrap();
+    assert_eq!(fs::read_to_string(file).unwrap(), "a = ${missing}\n");
+    assert!(matches!(unset(profile(), Some(dir), "b", None),
+        Err(CapoError::SettingMissing(_))));
+}
@@ -0,0 +1,4 @@
+pub mod args;
+pub mod settings;
+pub mod edit;
+pub mod output;
@@ -0,0 +1,176 @@
+#[allow(unused_imports)]
+use std::process::Command;
+#[allow(unused_imports)]
+use rustcapo::CapoError;
+#[allow(unused_imports)]
+use crate::output::{
+    render,
+    shell_name,
+    shell_quote,
+    Format,
+    Setting,
+};
+
+#[allow(dead_code)]
+fn setting(key: &str, value: &str) -> Setting {
+    Setting {
+        key: key.to_string(),
+        value: value.to_string(),
+        location: String::from("test.properties"),
+    }
+}
+
+/// Run a script with sh and get what it prints
+#[allow(dead_code)]
+fn sh(script: &str) -> String {
+    let output = Command::new("sh").arg("-c").arg(script).output().unwrap();
+    assert!(output.status.success(), "{}", script);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[allow(dead_code)]
+const HOSTILE: [&str; 6] = ["it's", "two\nlines", "$(echo run)", "`echo run`",
+    "", "'; echo run; '"];
+
+#[test]
+fn test_shell_quote_reads_back_unchanged() {
+    for value in HOSTILE {
+        let script = format!("printf %s {}", shell_quote(value));
+        assert_eq!(sh(&script), value, "{}", script);
+    }
+    assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    assert_eq!(shell_quote(""), "''");
+}
+
+#[test]
+fn test_export_reads_back_unchanged() {
+    let settings: Vec<Setting> = HOSTILE.iter().enumerate()
+        .map(|(i, v)| setting(&format!("section.key{}", i), v))
+        .collect();
+    let exports = render(Format::Export, &settings, false).unwrap();
+
+    for (i, value) in HOSTILE.iter().enumerate() {
+        let script = format!("{}printf %s \"$section_key{}\"", exports, i);
+        assert_eq!(sh(&script), *value, "{}", script);
+    }
+}
+
+#[test]
+fn test_shell_name() {
+    assert_eq!(shell_name("section1.database.user"), "section1_database_user");
+    assert_eq!(shell_name("Mixed-Case.key"), "Mixed_Case_key");
+    assert_eq!(shell_name("2nd-host"), "_2nd_host");
+    assert_eq!(shell_name("caf\u{e9}"), "caf_");
+    assert_eq!(shell_name(""), "_");
+}
+
+#[test]
+fn test_shell_names_collide() {
+    let settings = [setting("a.b", "1"), setting("a-b", "2")];
+    for format in [Format::Shell, Format::Env, Format::Export] {
+        match render(format, &settings, false) {
+            Err(CapoError::NameCollision { name, first, second }) => {
+                assert_eq!(name, "a_b");
+                assert_eq!(first, "a.b");
+                assert_eq!(second, "a-b");
+            },
+            _ => panic!("expected a NameCollision error"),
+        };
+    }
+
+    // Only the values are printed in quiet mode, and other formats keep the
+    // keys
+    assert!(render(Format::Shell, &settings, true).is_ok());
+    assert!(render(Format::Json, &settings, false).is_ok());
+}
+
+/// Values that every format has to escape or pass through unchanged
+#[allow(dead_code)]
+const ESCAPED: [&str; 7] = ["say \"hi\"", "C:\\path\\", "tab\there\r\n",
+    "bell\u{7}del\u{7f}", "caf\u{e9} \u{65e5}\u{672c}", "\u{1F600}", ""];
+
+#[allow(dead_code)]
+fn escaped_settings() -> Vec<Setting> {
+    ESCAPED.iter().enumerate()
+        .map(|(i, v)| setting(&format!("key.{}", i), v))
+        .collect()
+}
+
+#[test]
+fn test_json_parses() {
+    let rendered = render(Format::Json, &escaped_settings(), false).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+
+    for (i, value) in ESCAPED.iter().enumerate() {
+        let setting = &parsed[format!("key.{}", i)];
+        assert_eq!(setting["value"], *value);
+        assert_eq!(setting["location"], "test.properties");
+    }
+    assert_eq!(render(Format::Json, &[], false).unwrap(), "{}\n");
+}
+
+#[test]
+fn test_yaml_parses() {
+    let rendered = render(Format::Yaml, &escaped_settings(), false).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&rendered).unwrap();
+
+    for (i, value) in ESCAPED.iter().enumerate() {
+        let setting = &parsed[format!("key.{}", i).as_str()];
+        assert_eq!(setting["value"].as_str(), Some(*value));
+    }
+}
+
+#[test]
+fn test_toml_parses() {
+    let rendered = render(Format::Toml, &escaped_settings(), false).unwrap();
+    let parsed: toml::Table = toml::from_str(&rendered).unwrap();
+
+    for (i, value) in ESCAPED.iter().enumerate() {
+        let setting = &parsed[&format!("key.{}", i)];
+        assert_eq!(setting["value"].as_str(), Some(*value));
+    }
+}
+
+#[test]
+fn test_keys_differing_in_case_collide() {
+    let settings = [setting("a.b", "1"), setting("A.B", "2")];
+    for format in [Format::Json, Format::Yaml, Format::Toml,
+        Format::Properties] {
+
+        assert!(matches!(render(format, &settings, false),
+            Err(CapoError::NameCollision { .. })));
+    }
+}
+
+#[test]
+fn test_env_reads_back() {
+    let settings: Vec<Setting> = HOSTILE.iter()
+        .chain(ESCAPED.iter())
+        .filter(|v| !v.contains(['\n', '\r']))
+        .enumerate()
+        .map(|(i, v)| setting(&format!("key.{}", i), v))
+        .collect();
+    let rendered = render(Format::Env, &settings, false).unwrap();
+
+    for (i, setting) in settings.iter().enumerate() {
+        let script = format!("{}printf %s \"$key_{}\"", rendered, i);
+        assert_eq!(sh(&script), setting.value, "{}", script);
+    }
+
+    // Newlines are written the way env files escape them
+    let rendered = render(Format::Env, &[setting("a", "1\n2")], false).unwrap();
+    assert_eq!(rendered, "a=\"1\\n2\"\n");
+}
+
+#[test]
+fn test_null_records() {
+    let settings = [setting("a", "1\n2"), setting("b", "")];
+    assert_eq!(render(Format::Null, &settings, false).unwrap(),
+        "a=1\n2\0b=\0");
+    assert_eq!(render(Format::Null, &settings, true).unwrap(), "1\n2\0\0");
+
+    match render(Format::Null, &[setting("a", "1\u{0}2")], true) {
+        Err(CapoError::NulByte(key)) => assert_eq!(key, "a"),
+        _ => panic!("expected a NulByte error"),
+    };
+}
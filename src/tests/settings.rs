@@ -0,0 +1,79 @@
+#[allow(unused_imports)]
+use std::path::PathBuf;
+#[allow(unused_imports)]
+use rustcapo::{
+    CapoConfig,
+    CapoError,
+};
+#[allow(unused_imports)]
+use crate::{
+    select::Selector,
+    settings,
+};
+
+#[allow(dead_code)]
+fn config() -> CapoConfig {
+    CapoConfig::builder()
+        .ignore_environment()
+        .append_user_dir(false)
+        .profile("test")
+        .path(vec![PathBuf::from("src/lib/tests/profiles/one")])
+        .with_override("other.host", "other.example.org")
+        .build()
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn keys(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|k| k.to_string()).collect()
+}
+
+#[test]
+fn test_repeated_settings() {
+    let selector = Selector::new(&keys(&["archive."]), &[], &[]).unwrap();
+    let found = settings(&config(), keys(&["archive.host", "ARCHIVE.HOST",
+        "archive.port", "archive.host"]), &selector, false).unwrap();
+
+    let names: Vec<&str> = found.iter().map(|s| s.key.as_str()).collect();
+    assert_eq!(names, vec!["archive.host", "archive.port"]);
+
+    let found = settings(&config(), keys(&["archive.host", "Archive.Host"]),
+        &selector, true).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].key, "host");
+}
+
+#[test]
+fn test_stripped_names_collide() {
+    let selector = Selector::new(&keys(&["archive", "other"]), &[], &[])
+        .unwrap();
+
+    match settings(&config(), keys(&["archive.host", "other.host"]),
+        &selector, true) {
+
+        Err(CapoError::NameCollision { name, first, second }) => {
+            assert_eq!(name, "host");
+            assert_eq!(first, "archive.host");
+            assert_eq!(second, "other.host");
+        },
+        _ => panic!("expected a NameCollision error"),
+    };
+
+    // Without stripping the names are the keys, which don't collide
+    assert_eq!(settings(&config(), keys(&["archive.host", "other.host"]),
+        &selector, false).unwrap().len(), 2);
+}
+
+#[test]
+fn test_prefixes_change_length_in_upper_case() {
+    // `ı` and `ŉ` take a different number of bytes once upper cased
+    for prefix in ["ıtems", "ITEMS"] {
+        let selector = Selector::new(&keys(&[prefix]), &[], &[]).unwrap();
+        assert!(selector.matches("ıtems.ŉame"));
+        assert_eq!(selector.strip("ıtems.ŉame"), "ŉame");
+    }
+
+    let selector = Selector::new(&keys(&["ʼNODES"]), &[], &[]).unwrap();
+    assert_eq!(selector.strip("ŉodes.0"), "0");
+    assert!(!selector.matches("nodes.0"));
+}